@@ -1,13 +1,153 @@
-use std::hash::Hash;
-
 use rand::Rng;
 
-/// A cell that knows how to get its next state and a random verion of its type
-pub trait CellLike: Default + Clone + Copy + Hash + Eq {
+use crate::common::{DoubleVec, History};
+
+/// A cell that knows how to get its next state and a random verion of its type. Cells
+/// only need to be comparable, so continuous states (e.g. `f32`s) are fine
+pub trait CellLike: Default + Clone + Copy + PartialEq {
     fn next_state(&self) -> Self;
     fn random<R: Rng + ?Sized>(rng: &mut R) -> Self;
 }
 
+/// A [CellLike] whose states can be listed and mapped to small integers, which is what
+/// palettes, file formats and histograms need to stay generic over the cell type
+pub trait FiniteCellLike: CellLike {
+    /// How many distinct states the cell has
+    const STATE_COUNT: usize;
+
+    /// Maps the state to an integer in `0..STATE_COUNT`
+    fn to_index(&self) -> usize;
+
+    /// The inverse of [FiniteCellLike::to_index]
+    fn from_index(index: usize) -> Option<Self>;
+
+    /// A human readable name of the state
+    fn state_name(&self) -> &'static str;
+
+    /// All the states, ordered by their index
+    fn states() -> Vec<Self> {
+        (0..Self::STATE_COUNT)
+            .filter_map(Self::from_index)
+            .collect()
+    }
+}
+
+/// Counts how many cells of the grid are in each state, indexed by [FiniteCellLike::to_index]
+pub fn histogram<C: FiniteCellLike>(cells: &DoubleVec<C>) -> Vec<usize> {
+    let mut counts = vec![0; C::STATE_COUNT];
+    for cell in cells.iter().flatten() {
+        counts[cell.to_index()] += 1;
+    }
+    counts
+}
+
+/// The [histogram] of a world after every tick, which is what population plots are
+/// drawn from. Only the last ticks are kept, see [History]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    rows: History<Vec<usize>>,
+}
+
+impl Census {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A census that keeps the last `capacity` ticks, and at least one
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            rows: History::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.rows.capacity()
+    }
+
+    /// The rows kept, oldest first
+    pub fn rows(&self) -> impl Iterator<Item = &Vec<usize>> {
+        self.rows.iter()
+    }
+
+    /// The tick of the oldest row kept
+    pub fn first_tick(&self) -> usize {
+        self.rows.dropped()
+    }
+
+    /// The counts of the last tick, or of the edits made since
+    pub fn last(&self) -> Option<&Vec<usize>> {
+        self.rows.last()
+    }
+
+    /// Counts the states of `cells` as the next row, dropping the oldest one when full
+    pub fn record<C: FiniteCellLike>(&mut self, cells: &DoubleVec<C>) {
+        self.rows.push(histogram(cells));
+    }
+
+    /// Counts the states of `cells` again for the last row, after they were edited (e.g.
+    /// by painting) between two ticks
+    pub fn revise<C: FiniteCellLike>(&mut self, cells: &DoubleVec<C>) {
+        self.rows.revise(histogram(cells));
+    }
+
+    /// How many cells were in `state` on every row
+    pub fn series<C: FiniteCellLike>(&self, state: C) -> Vec<usize> {
+        self.rows.iter().map(|row| row[state.to_index()]).collect()
+    }
+
+    /// The last row as `name count` pairs, headed by the [FiniteCellLike::state_name]s
+    pub fn summary<C: FiniteCellLike>(&self) -> String {
+        let counts = self.last().cloned().unwrap_or_default();
+        C::states()
+            .iter()
+            .zip(counts)
+            .map(|(state, count)| format!("{} {}", state.state_name(), count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The rows as comma separated values, headed by the [FiniteCellLike::state_name]s
+    pub fn to_csv<C: FiniteCellLike>(&self) -> String {
+        let names: Vec<&str> = C::states().iter().map(C::state_name).collect();
+        let mut csv = format!("tick,{}\n", names.join(","));
+        for (tick, row) in (self.first_tick()..).zip(self.rows.iter()) {
+            let counts: Vec<String> = row.iter().map(usize::to_string).collect();
+            csv += &format!("{},{}\n", tick, counts.join(","));
+        }
+        csv
+    }
+}
+
+/// Implements [FiniteCellLike] for a fieldless enum, numbering the listed variants
+/// in the order they are given
+///
+/// ```ignore
+/// finite_cell!(Cell { Dead => "Dead", Alive => "Alive" });
+/// ```
+#[macro_export]
+macro_rules! finite_cell {
+    ($cell:ty { $($variant:ident => $name:literal),+ $(,)? }) => {
+        impl $crate::cell::FiniteCellLike for $cell {
+            const STATE_COUNT: usize = [$($name),+].len();
+
+            fn to_index(&self) -> usize {
+                [$(Self::$variant),+]
+                    .iter()
+                    .position(|state| state == self)
+                    .expect("every variant is listed")
+            }
+
+            fn from_index(index: usize) -> Option<Self> {
+                [$(Self::$variant),+].get(index).copied()
+            }
+
+            fn state_name(&self) -> &'static str {
+                [$($name),+][self.to_index()]
+            }
+        }
+    };
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
@@ -24,4 +164,66 @@ pub(crate) mod test {
             Cell
         }
     }
+
+    #[derive(Default, Clone, Copy, Debug, Hash, Eq, PartialEq)]
+    enum Tri {
+        #[default]
+        Zero,
+        One,
+        Two,
+    }
+
+    impl CellLike for Tri {
+        fn next_state(&self) -> Self {
+            Self::from_index((self.to_index() + 1) % Self::STATE_COUNT).unwrap()
+        }
+
+        fn random<R: Rng + ?Sized>(_rng: &mut R) -> Self {
+            Tri::Zero
+        }
+    }
+
+    finite_cell!(Tri { Zero => "zero", One => "one", Two => "two" });
+
+    #[test]
+    fn finite_cell_round_trips() {
+        assert_eq!(Tri::STATE_COUNT, 3);
+        assert_eq!(Tri::states(), vec![Tri::Zero, Tri::One, Tri::Two]);
+        for state in Tri::states() {
+            assert_eq!(Tri::from_index(state.to_index()), Some(state));
+        }
+        assert_eq!(Tri::from_index(3), None);
+        assert_eq!(Tri::Two.state_name(), "two");
+        assert_eq!(Tri::One.next_state(), Tri::Two);
+    }
+
+    #[test]
+    fn histogram_counts_states() {
+        let cells = vec![vec![Tri::Zero, Tri::Two], vec![Tri::Two, Tri::Two]];
+        assert_eq!(histogram(&cells), vec![1, 0, 3]);
+
+        let mut census = Census::new();
+        census.record(&cells);
+        census.record(&vec![vec![Tri::One]]);
+        assert_eq!(census.series(Tri::Two), vec![3, 0]);
+        assert_eq!(
+            census.to_csv::<Tri>(),
+            "tick,zero,one,two\n0,1,0,3\n1,0,1,0\n"
+        );
+
+        // edits between ticks replace the last row
+        census.revise(&vec![vec![Tri::Two]]);
+        assert_eq!(census.summary::<Tri>(), "zero 0, one 0, two 1");
+
+        let mut recent = Census::with_capacity(2);
+        for n in 0..5 {
+            recent.record(&vec![vec![Tri::One; n]]);
+        }
+        assert_eq!(recent.first_tick(), 3);
+        assert_eq!(recent.series(Tri::One), vec![3, 4]);
+        assert_eq!(
+            recent.to_csv::<Tri>(),
+            "tick,zero,one,two\n3,0,3,0\n4,0,4,0\n"
+        );
+    }
 }
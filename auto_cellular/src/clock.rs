@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use crate::{
+    space::{OutputField, SpaceLike},
+    world::WorldLike,
+};
+
+/// How fast a [Clock] should tick and render
+#[derive(Clone, Copy, Debug)]
+pub struct ClockConfig {
+    /// Target ticks per second at a speed of 1
+    pub tps: f64,
+    /// Target frames per second, which is how often the frontend should call [Clock::drive]
+    pub fps: f64,
+    /// Ticks beyond this many in one frame are skipped instead of piling up
+    pub max_ticks_per_frame: usize,
+}
+
+impl ClockConfig {
+    pub fn new(tps: f64, fps: f64) -> Self {
+        Self {
+            tps,
+            fps,
+            max_ticks_per_frame: 16,
+        }
+    }
+
+    /// How long a frame should take
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps.max(f64::EPSILON))
+    }
+}
+
+/// Measures how often something happens over windows of about a second
+#[derive(Clone, Debug, Default)]
+pub struct RateMeter {
+    window_start: Option<Duration>,
+    count: usize,
+    rate: f64,
+}
+
+impl RateMeter {
+    /// `n` events happened at `now`
+    pub fn record(&mut self, now: Duration, n: usize) {
+        let start = *self.window_start.get_or_insert(now);
+        self.count += n;
+        let elapsed = now.saturating_sub(start);
+        if elapsed >= Duration::from_secs(1) {
+            self.rate = self.count as f64 / elapsed.as_secs_f64();
+            self.count = 0;
+            self.window_start = Some(now);
+        }
+    }
+
+    /// Events per second over the last complete window
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
+
+/// Decides how many ticks a [WorldLike] should go through every frame, so that the tick
+/// rate does not depend on how fast the frontend renders.
+///
+/// Time is passed in by the frontend as a [Duration] since any fixed origin, which
+/// keeps the clock usable where [std::time::Instant] is not (e.g. in the browser).
+#[derive(Clone, Debug)]
+pub struct Clock {
+    config: ClockConfig,
+    speed: f64,
+    paused: bool,
+    steps: usize,
+    last: Option<Duration>,
+    owed: f64,
+    skipped: usize,
+    tps: RateMeter,
+    fps: RateMeter,
+}
+
+impl Clock {
+    pub fn new(config: ClockConfig) -> Self {
+        Self {
+            config,
+            speed: 1.0,
+            paused: false,
+            steps: 0,
+            last: None,
+            owed: 0.0,
+            skipped: 0,
+            tps: RateMeter::default(),
+            fps: RateMeter::default(),
+        }
+    }
+
+    pub fn config(&self) -> &ClockConfig {
+        &self.config
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Queue a single tick for the next frame, only honored while paused
+    pub fn step(&mut self) {
+        if self.paused {
+            self.steps += 1;
+        }
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Multiplies the configured ticks per second
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed.max(0.0);
+    }
+
+    pub fn faster(&mut self) {
+        self.set_speed(self.speed * 2.0);
+    }
+
+    pub fn slower(&mut self) {
+        self.set_speed(self.speed / 2.0);
+    }
+
+    /// The ticks per second the clock is aiming for
+    pub fn target_tps(&self) -> f64 {
+        self.config.tps * self.speed
+    }
+
+    /// Achieved ticks per second
+    pub fn tps(&self) -> f64 {
+        self.tps.rate()
+    }
+
+    /// Achieved frames per second
+    pub fn fps(&self) -> f64 {
+        self.fps.rate()
+    }
+
+    /// Ticks that were dropped because a frame would have needed too many of them
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// A frame starts at `now`. Returns how many ticks are due in it
+    pub fn frame(&mut self, now: Duration) -> usize {
+        let elapsed = self
+            .last
+            .map_or(Duration::ZERO, |last| now.saturating_sub(last));
+        self.last = Some(now);
+
+        let ticks = if self.paused {
+            self.owed = 0.0;
+            std::mem::take(&mut self.steps)
+        } else {
+            self.owed += elapsed.as_secs_f64() * self.target_tps();
+            let due = self.owed.floor();
+            self.owed -= due;
+
+            let due = due as usize;
+            let max = self.config.max_ticks_per_frame;
+            if due > max {
+                self.skipped += due - max;
+            }
+            due.min(max)
+        };
+
+        self.fps.record(now, 1);
+        self.tps.record(now, ticks);
+        ticks
+    }
+
+    /// Runs one frame of a [SpaceLike]: ticks its world as many times as are due and
+    /// redraws it. Returns the number of ticks that happened
+    pub fn drive<W, O, S>(&mut self, space: &mut S, now: Duration) -> Result<usize, String>
+    where
+        W: WorldLike,
+        O: OutputField<W::Cell, S::CellRepr>,
+        S: SpaceLike<W, O>,
+    {
+        let ticks = self.frame(now);
+        match ticks {
+            0 => {}
+            1 => space.tick_delta()?,
+            _ => {
+                for _ in 0..ticks {
+                    space.world_mut().tick();
+                }
+                space.draw_whole()?
+            }
+        }
+        Ok(ticks)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn secs(s: f64) -> Duration {
+        Duration::from_secs_f64(s)
+    }
+
+    #[test]
+    fn ticks_follow_target_rate() {
+        let mut clock = Clock::new(ClockConfig::new(10.0, 60.0));
+        assert_eq!(clock.frame(secs(0.0)), 0);
+        assert_eq!(clock.frame(secs(0.05)), 0);
+        assert_eq!(clock.frame(secs(0.1)), 1);
+        assert_eq!(clock.frame(secs(0.35)), 2);
+
+        clock.set_speed(2.0);
+        assert_eq!(clock.frame(secs(0.6)), 5);
+    }
+
+    #[test]
+    fn excess_ticks_are_skipped() {
+        let mut clock = Clock::new(ClockConfig::new(100.0, 60.0));
+        clock.frame(secs(0.0));
+        assert_eq!(clock.frame(secs(1.0)), 16);
+        assert_eq!(clock.skipped(), 84);
+    }
+
+    #[test]
+    fn paused_clock_only_steps() {
+        let mut clock = Clock::new(ClockConfig::new(10.0, 60.0));
+        clock.pause();
+        clock.frame(secs(0.0));
+        assert_eq!(clock.frame(secs(5.0)), 0);
+
+        clock.step();
+        clock.step();
+        assert_eq!(clock.frame(secs(5.1)), 2);
+        assert_eq!(clock.frame(secs(5.2)), 0);
+
+        // steps are ignored while running, so a frame with no time passed ticks nothing
+        clock.resume();
+        clock.step();
+        clock.step();
+        assert_eq!(clock.frame(secs(5.2)), 0);
+        assert_eq!(clock.frame(secs(5.3)), 1);
+    }
+
+    #[test]
+    fn rates_are_measured() {
+        let mut clock = Clock::new(ClockConfig::new(30.0, 60.0));
+        for frame in 0..=90 {
+            clock.frame(secs(frame as f64 / 60.0));
+        }
+        assert!((clock.fps() - 60.0).abs() < 2.0);
+        assert!((clock.tps() - 30.0).abs() < 2.0);
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 /// Index of a cell in a world
 pub type Index = (usize, usize);
 
@@ -18,3 +20,117 @@ pub fn linearize<T>(vector: DoubleVec<T>) -> Vec<(Index, T)> {
         })
         .collect()
 }
+
+/// The cells of `after` that differ from `before`. Worlds that update their cells one
+/// after another on a scratch grid, rather than all at once, get their changes this way
+pub fn diff<T: PartialEq + Copy>(before: &DoubleVec<T>, after: &DoubleVec<T>) -> Vec<(Index, T)> {
+    before
+        .iter()
+        .zip(after)
+        .enumerate()
+        .flat_map(|(j, (old, new))| {
+            old.iter()
+                .zip(new)
+                .enumerate()
+                .filter(|(_, (old, new))| old != new)
+                .map(move |(i, (_, &new))| ((i, j), new))
+        })
+        .collect()
+}
+
+/// The last values of something measured over and over, such as an index of a world
+/// after every tick. Only the last [History::capacity] are kept, so long runs do not
+/// grow it without bound
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct History<T> {
+    values: VecDeque<T>,
+    /// How many values were dropped to make room for newer ones
+    dropped: usize,
+    capacity: usize,
+}
+
+/// How many values a [History::new] keeps
+pub const HISTORY_CAPACITY: usize = 10_000;
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::with_capacity(HISTORY_CAPACITY)
+    }
+}
+
+impl<T> History<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A history that keeps the last `capacity` values, and at least one
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: VecDeque::new(),
+            dropped: 0,
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// How many values were pushed before the oldest one kept, which is its tick for
+    /// histories that get a value at creation and after every tick
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The values kept, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.values.back()
+    }
+
+    /// Adds `value` as the newest one, dropping the oldest one when full
+    pub fn push(&mut self, value: T) {
+        self.values.push_back(value);
+        if self.values.len() > self.capacity {
+            self.values.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    /// Replaces the newest value, e.g. when what was measured changed since, or pushes
+    /// `value` when there is none
+    pub fn revise(&mut self, value: T) {
+        match self.values.back_mut() {
+            Some(last) => *last = value,
+            None => self.push(value),
+        }
+    }
+}
+
+impl<T> std::ops::Index<usize> for History<T> {
+    type Output = T;
+
+    /// The `index`th value kept, counting from the oldest
+    fn index(&self, index: usize) -> &T {
+        &self.values[index]
+    }
+}
+
+impl<T> Extend<T> for History<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use crate::common::{Dimensions, DoubleVec, Index};
+
+/// A rectangle of the output that changed, along with the new units that fill it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirtyRect<S> {
+    /// Top left corner of the rectangle
+    pub origin: Index,
+    /// The new units, one row at a time
+    pub units: DoubleVec<S>,
+}
+
+impl<S> DirtyRect<S> {
+    pub fn dimensions(&self) -> Dimensions {
+        Dimensions(
+            self.units.first().map_or(0, |row| row.len()),
+            self.units.len(),
+        )
+    }
+}
+
+/// Deltas with fewer changes than this are not worth sorting into [DirtyRect]s, and are
+/// set one unit at a time
+pub const COALESCE_THRESHOLD: usize = 16;
+
+/// Merges a delta into rectangles that cover exactly the changed [Index]s, so that
+/// an [crate::space::OutputField] can push whole spans at once instead of one unit at a time.
+///
+/// Changes on the same row are first joined into horizontal runs, then runs that span the
+/// same columns on consecutive rows are stacked into a single rectangle. If an [Index]
+/// appears more than once, the last change wins.
+pub fn coalesce<S>(delta: impl IntoIterator<Item = (Index, S)>) -> Vec<DirtyRect<S>> {
+    let mut changes: Vec<(Index, S)> = delta.into_iter().collect();
+    changes.reverse();
+    changes.sort_by_key(|&((x, y), _)| (y, x));
+    changes.dedup_by_key(|&mut (index, _)| index);
+
+    let mut rects: Vec<DirtyRect<S>> = vec![];
+    // rectangles that may still grow downwards, keyed by the columns they span
+    let mut open: HashMap<(usize, usize), usize> = HashMap::new();
+
+    let mut changes = changes.into_iter().peekable();
+    while let Some(((x, y), unit)) = changes.next() {
+        let mut run = vec![unit];
+        while let Some(&((nx, ny), _)) = changes.peek() {
+            if ny != y || nx != x + run.len() {
+                break;
+            }
+            run.push(changes.next().unwrap().1);
+        }
+
+        let key = (x, run.len());
+        match open.get(&key) {
+            Some(&i) if rects[i].origin.1 + rects[i].units.len() == y => rects[i].units.push(run),
+            _ => {
+                open.insert(key, rects.len());
+                rects.push(DirtyRect {
+                    origin: (x, y),
+                    units: vec![run],
+                });
+            }
+        }
+    }
+
+    rects
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coalesce_merges_runs_into_rects() {
+        let delta = vec![
+            ((1, 0), 'a'),
+            ((2, 0), 'b'),
+            ((1, 1), 'c'),
+            ((2, 1), 'd'),
+            ((5, 1), 'e'),
+        ];
+        let rects = coalesce(delta);
+
+        assert_eq!(
+            rects,
+            vec![
+                DirtyRect {
+                    origin: (1, 0),
+                    units: vec![vec!['a', 'b'], vec!['c', 'd']],
+                },
+                DirtyRect {
+                    origin: (5, 1),
+                    units: vec![vec!['e']],
+                },
+            ]
+        );
+        assert_eq!(rects[0].dimensions().0, 2);
+        assert_eq!(rects[0].dimensions().1, 2);
+    }
+
+    #[test]
+    fn coalesce_keeps_last_change_and_gaps() {
+        let delta = vec![((0, 0), 1), ((0, 2), 2), ((0, 0), 3)];
+        let rects = coalesce(delta);
+
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[0].units, vec![vec![3]]);
+        assert_eq!(rects[1].origin, (0, 2));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::common::{Dimensions, Index};
+
+/// How the cells of a world are laid out and who their neighbors are
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// Square cells with 8 (Moore) neighbors
+    #[default]
+    Square,
+    /// Pointy topped hexagons with 6 neighbors. Rows are stored as-is and every odd
+    /// row is drawn half a cell to the right ("odd-r" offset rows), so an even number
+    /// of rows wraps around seamlessly
+    Hex,
+}
+
+/// Axial coordinates of a hexagon: `q` grows to the right and `r` downwards, with the
+/// third cube coordinate being `-q - r`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Axial {
+    pub q: isize,
+    pub r: isize,
+}
+
+impl Axial {
+    /// The 6 neighbors of the origin, clockwise starting from the right
+    pub const DIRECTIONS: [Axial; 6] = [
+        Axial { q: 1, r: 0 },
+        Axial { q: 0, r: 1 },
+        Axial { q: -1, r: 1 },
+        Axial { q: -1, r: 0 },
+        Axial { q: 0, r: -1 },
+        Axial { q: 1, r: -1 },
+    ];
+
+    pub fn new(q: isize, r: isize) -> Self {
+        Self { q, r }
+    }
+
+    /// The hexagon stored at `(x, y)` of an odd-r grid
+    pub fn from_index((x, y): Index) -> Self {
+        let (x, y) = (x as isize, y as isize);
+        Self::new(x - (y - (y & 1)) / 2, y)
+    }
+
+    /// Where the hexagon is stored in an odd-r grid, wrapping around its edges
+    pub fn to_index(&self, Dimensions(w, h): Dimensions) -> Index {
+        let x = self.q + (self.r - (self.r & 1)) / 2;
+        (
+            x.rem_euclid(w as isize) as usize,
+            self.r.rem_euclid(h as isize) as usize,
+        )
+    }
+
+    pub fn neighbor(&self, direction: usize) -> Self {
+        let d = Self::DIRECTIONS[direction % 6];
+        Self::new(self.q + d.q, self.r + d.r)
+    }
+
+    /// Steps between two hexagons
+    pub fn distance(&self, other: &Self) -> usize {
+        let (dq, dr) = (self.q - other.q, self.r - other.r);
+        ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as usize
+    }
+
+    /// The hexagon fractional axial coordinates fall in
+    pub fn round(q: f64, r: f64) -> Self {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Self::new(rq as isize, rr as isize)
+    }
+}
+
+/// The 6 neighbors of the cell at `p` in an odd-r grid, wrapping around the edges
+pub fn neighbors(p: Index, dimensions: Dimensions) -> Vec<Index> {
+    let hex = Axial::from_index(p);
+    (0..6)
+        .map(|d| hex.neighbor(d).to_index(dimensions))
+        .collect()
+}
+
+/// Where the hexagons of an odd-r grid land on screen, for hexagons `width` units wide
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Layout {
+    pub width: f64,
+}
+
+impl Layout {
+    pub fn new(width: f64) -> Self {
+        Self { width }
+    }
+
+    /// Distance from the center of a hexagon to its corners
+    pub fn radius(&self) -> f64 {
+        self.width / 3f64.sqrt()
+    }
+
+    /// Vertical distance between the centers of two consecutive rows
+    pub fn row_height(&self) -> f64 {
+        self.radius() * 1.5
+    }
+
+    /// How many hexagons fit in an area of the given size, with an even number of rows so
+    /// that the grid wraps around, see [Topology::Hex]
+    pub fn fit(&self, Dimensions(w, h): Dimensions) -> Dimensions {
+        let columns = (w as f64 - self.width / 2.0) / self.width;
+        let rows = (h as f64 - self.radius() / 2.0) / self.row_height();
+        Dimensions(columns.max(1.0) as usize, rows.max(2.0) as usize & !1)
+    }
+
+    pub fn center(&self, (x, y): Index) -> (f64, f64) {
+        let shift = if y % 2 == 1 { 0.5 } else { 0.0 };
+        (
+            self.width * (x as f64 + shift + 0.5),
+            self.row_height() * y as f64 + self.radius(),
+        )
+    }
+
+    /// The corners of the hexagon at `index`, clockwise starting from the top
+    pub fn corners(&self, index: Index) -> [(f64, f64); 6] {
+        let (cx, cy) = self.center(index);
+        let (half, radius) = (self.width / 2.0, self.radius());
+        [
+            (cx, cy - radius),
+            (cx + half, cy - radius / 2.0),
+            (cx + half, cy + radius / 2.0),
+            (cx, cy + radius),
+            (cx - half, cy + radius / 2.0),
+            (cx - half, cy - radius / 2.0),
+        ]
+    }
+
+    /// The whole units covered by the hexagon at `index`, as horizontal spans of
+    /// `(x, y, length)`. A unit belongs to the hexagon its center is in, so the spans of
+    /// neighboring hexagons tile without gaps or overlaps
+    pub fn spans(&self, index: Index) -> Vec<(isize, isize, usize)> {
+        let (cx, cy) = self.center(index);
+        let (half, radius) = (self.width / 2.0, self.radius());
+        let top = (cy - radius - 0.5).ceil() as isize;
+        let bottom = (cy + radius - 0.5).floor() as isize;
+        (top..=bottom)
+            .filter_map(|y| {
+                let dy = (y as f64 + 0.5 - cy).abs();
+                let reach = if dy <= radius / 2.0 {
+                    half
+                } else {
+                    half * (radius - dy) / (radius / 2.0)
+                };
+                let left = (cx - reach - 0.5).ceil() as isize;
+                let right = (cx + reach - 0.5).floor() as isize;
+                (right >= left).then(|| (left, y, (right - left + 1) as usize))
+            })
+            .collect()
+    }
+
+    /// The cell of a `dimensions` grid under the point `(x, y)`, if there is one
+    pub fn pick(&self, (x, y): (f64, f64), dimensions: Dimensions) -> Option<Index> {
+        let radius = self.radius();
+        let (x, y) = (x - self.width / 2.0, y - radius);
+        let q = (3f64.sqrt() / 3.0 * x - y / 3.0) / radius;
+        let r = (2.0 / 3.0 * y) / radius;
+        let hex = Axial::round(q, r);
+        let column = hex.q + (hex.r - (hex.r & 1)) / 2;
+        let Dimensions(w, h) = dimensions;
+        ((0..w as isize).contains(&column) && (0..h as isize).contains(&hex.r))
+            .then_some((column as usize, hex.r as usize))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn neighbors_are_one_step_away() {
+        let dimensions = Dimensions(8, 6);
+        for p in [(3, 2), (3, 3), (0, 0), (7, 5)] {
+            let around = neighbors(p, dimensions);
+            assert_eq!(around.iter().collect::<HashSet<_>>().len(), 6);
+            for n in around {
+                // every neighbor has us as its neighbor
+                assert!(neighbors(n, dimensions).contains(&p));
+            }
+        }
+
+        let center = Axial::from_index((3, 3));
+        assert_eq!(center.to_index(dimensions), (3, 3));
+        assert_eq!(neighbors((3, 3), dimensions)[..2], [(4, 3), (4, 4)]);
+        assert_eq!(center.distance(&Axial::from_index((5, 1))), 3);
+    }
+
+    #[test]
+    fn picking_finds_the_hexagon_drawn_there() {
+        let layout = Layout::new(10.0);
+        let dimensions = Dimensions(6, 6);
+        let mut covered = HashSet::new();
+        for y in 0..6 {
+            for x in 0..6 {
+                let center = layout.center((x, y));
+                assert_eq!(layout.pick(center, dimensions), Some((x, y)));
+                for corner in layout.corners((x, y)) {
+                    // a little inside the corner is still the same hexagon
+                    let inside = (
+                        corner.0 + (center.0 - corner.0) * 0.1,
+                        corner.1 + (center.1 - corner.1) * 0.1,
+                    );
+                    assert_eq!(layout.pick(inside, dimensions), Some((x, y)));
+                }
+
+                for (left, row, length) in layout.spans((x, y)) {
+                    for unit in left..left + length as isize {
+                        assert!(covered.insert((unit, row)), "units are drawn once");
+                        let point = (unit as f64 + 0.5, row as f64 + 0.5);
+                        assert_eq!(layout.pick(point, dimensions), Some((x, y)));
+                    }
+                }
+            }
+        }
+        assert_eq!(layout.pick((-20.0, 5.0), dimensions), None);
+    }
+
+    #[test]
+    fn fitted_grids_have_even_rows() {
+        let layout = Layout::new(10.0);
+        for h in 0..100 {
+            let Dimensions(_, rows) = layout.fit(Dimensions(100, h));
+            assert!(rows >= 2 && rows % 2 == 0, "{} rows fit in {}", rows, h);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    cell::CellLike,
+    common::Index,
+    world::{WorldConfig, WorldLike},
+};
+
+/// A mouse button, as far as the frontends agree on them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Button {
+    Primary,
+    Secondary,
+    Middle,
+}
+
+/// Modifier keys held while a [Button] was pressed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self {
+        shift: false,
+        ctrl: false,
+        alt: false,
+    };
+
+    pub const SHIFT: Self = Self {
+        shift: true,
+        ctrl: false,
+        alt: false,
+    };
+}
+
+/// What pressing a [Button] does to the [CellLike]s under the pointer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action<C> {
+    /// Click the cell, see [WorldLike::click], which moves it to its
+    /// [CellLike::next_state] unless the world does something else with clicks
+    Cycle,
+    /// Set the cell to the primary state of the [Input]
+    Paint,
+    /// Set the cell back to its default
+    Erase,
+    /// Set the cell to a specific state
+    Set(C),
+}
+
+struct Stroke<C> {
+    action: Action<C>,
+    last: Index,
+    visited: HashSet<Index>,
+}
+
+/// Turns button presses and drags into edits of a [WorldLike].
+///
+/// Each (button, modifiers) pair is bound to an [Action]. A drag continues the
+/// action of the press that started it, touching every cell on the line between two
+/// consecutive pointer positions, but each cell at most once per stroke.
+pub struct Input<C> {
+    bindings: HashMap<(Button, Modifiers), Action<C>>,
+    primary: C,
+    stroke: Option<Stroke<C>>,
+}
+
+impl<C: CellLike> Default for Input<C> {
+    /// Primary cycles, shift + primary and middle paint and secondary erases. The primary
+    /// state is the one that follows the default state
+    fn default() -> Self {
+        let mut input = Self {
+            bindings: HashMap::new(),
+            primary: C::default().next_state(),
+            stroke: None,
+        };
+        input.bind(Button::Primary, Modifiers::NONE, Action::Cycle);
+        input.bind(Button::Primary, Modifiers::SHIFT, Action::Paint);
+        input.bind(Button::Middle, Modifiers::NONE, Action::Paint);
+        input.bind(Button::Secondary, Modifiers::NONE, Action::Erase);
+        input
+    }
+}
+
+impl<C: CellLike> Input<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An [Input] without any bindings
+    pub fn unbound(primary: C) -> Self {
+        Self {
+            bindings: HashMap::new(),
+            primary,
+            stroke: None,
+        }
+    }
+
+    pub fn primary(&self) -> C {
+        self.primary
+    }
+
+    /// Set the state that [Action::Paint] uses
+    pub fn set_primary(&mut self, primary: C) {
+        self.primary = primary;
+    }
+
+    pub fn bind(&mut self, button: Button, modifiers: Modifiers, action: Action<C>) {
+        self.bindings.insert((button, modifiers), action);
+    }
+
+    pub fn unbind(&mut self, button: Button, modifiers: Modifiers) {
+        self.bindings.remove(&(button, modifiers));
+    }
+
+    /// The [Action] bound to a [Button] with the given [Modifiers], falling back to the
+    /// binding of the bare [Button]
+    pub fn action(&self, button: Button, modifiers: Modifiers) -> Option<Action<C>> {
+        self.bindings
+            .get(&(button, modifiers))
+            .or_else(|| self.bindings.get(&(button, Modifiers::NONE)))
+            .copied()
+    }
+
+    /// Whether a press started a stroke that was not released yet
+    pub fn is_stroking(&self) -> bool {
+        self.stroke.is_some()
+    }
+
+    /// A [Button] went down over the cell at `at`. The changes it made become the
+    /// [WorldLike::delta] of the `world`
+    pub fn press<W>(&mut self, world: &mut W, at: Index, button: Button, modifiers: Modifiers)
+    where
+        W: WorldLike<Cell = C>,
+    {
+        self.stroke = self.action(button, modifiers).map(|action| Stroke {
+            action,
+            last: at,
+            visited: HashSet::new(),
+        });
+        self.apply(world, vec![at]);
+    }
+
+    /// The pointer moved to `to` while a [Button] was held
+    pub fn drag<W>(&mut self, world: &mut W, to: Index)
+    where
+        W: WorldLike<Cell = C>,
+    {
+        if let Some(stroke) = &mut self.stroke {
+            let from = std::mem::replace(&mut stroke.last, to);
+            self.apply(world, line(from, to));
+        }
+    }
+
+    /// The [Button] was released, which ends the stroke
+    pub fn release(&mut self) {
+        self.stroke = None;
+    }
+
+    fn apply<W>(&mut self, world: &mut W, indices: Vec<Index>)
+    where
+        W: WorldLike<Cell = C>,
+    {
+        let primary = self.primary;
+        let stroke = match &mut self.stroke {
+            Some(stroke) => stroke,
+            None => return,
+        };
+
+        let dimensions = *world.config().dimensions();
+        let indices = indices
+            .into_iter()
+            .filter(|&(x, y)| x < dimensions.0 && y < dimensions.1)
+            .filter(|&index| stroke.visited.insert(index));
+        let cell = match stroke.action {
+            // cycling is a click, which worlds may do more with than changing the cell
+            Action::Cycle => {
+                let mut delta = vec![];
+                for i in indices {
+                    world.click(i);
+                    delta.append(world.delta_mut());
+                }
+                *world.delta_mut() = delta;
+                return;
+            }
+            Action::Paint => primary,
+            Action::Erase => C::default(),
+            Action::Set(cell) => cell,
+        };
+        world.set_cells(indices.map(|i| (i, cell)).collect());
+    }
+}
+
+/// The [Index]s on the straight line between `from` and `to`, both included
+pub fn line(from: Index, to: Index) -> Vec<Index> {
+    let (mut x, mut y) = (from.0 as isize, from.1 as isize);
+    let (x1, y1) = (to.0 as isize, to.1 as isize);
+    let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+    let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+    let mut error = dx + dy;
+
+    let mut points = vec![];
+    loop {
+        points.push((x as usize, y as usize));
+        if x == x1 && y == y1 {
+            break points;
+        }
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn line_is_connected() {
+        assert_eq!(line((0, 0), (3, 1)), vec![(0, 0), (1, 0), (2, 1), (3, 1)]);
+        assert_eq!(line((2, 2), (2, 0)), vec![(2, 2), (2, 1), (2, 0)]);
+        assert_eq!(line((1, 1), (1, 1)), vec![(1, 1)]);
+    }
+}
@@ -1,4 +1,8 @@
 pub mod cell;
+pub mod clock;
 pub mod common;
+pub mod dirty;
+pub mod hex;
+pub mod input;
 pub mod space;
 pub mod world;
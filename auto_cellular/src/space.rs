@@ -1,9 +1,18 @@
 use crate::{
     cell::CellLike,
     common::{DoubleVec, Index},
+    dirty::{coalesce, DirtyRect, COALESCE_THRESHOLD},
     world::WorldLike,
 };
 
+/// Gives what a space draws over the cells of a world, see [SpaceLike::overlay]
+pub type Overlayer<W, S> = fn(&W) -> Vec<(Index, S)>;
+
+/// The [Overlayer] of spaces with nothing to draw over the cells
+pub fn no_overlay<W, S>(_: &W) -> Vec<(Index, S)> {
+    vec![]
+}
+
 /// A [Space] (like a Gui) where a [BasicWorld] can show its [BasicCell]s
 /// It only cares about learning how to lay the cells in the world
 pub trait SpaceLike<W, O>
@@ -28,22 +37,38 @@ where
     /// Get the representer of the [Space]
     fn reprer(&self) -> Self::Reprer;
 
-    /// (Re)draw the whole [BasicWorld] in the [OutputField]
+    /// What is drawn over the cells and where, like agents that walk over the cells
+    /// rather than live in them. Nothing by default
+    fn overlay(&self) -> Vec<(Index, Self::CellRepr)> {
+        vec![]
+    }
+
+    /// (Re)draw the whole [BasicWorld] in the [OutputField], with the [SpaceLike::overlay]
+    /// on top
     fn draw_whole(&mut self) -> Result<(), String> {
-        let data = self
+        let mut data: DoubleVec<Self::CellRepr> = self
             .world()
             .cells()
             .iter()
             .map(|row| row.iter().map(|cell| self.reprer()(*cell)).collect())
             .collect();
+        for ((x, y), unit) in self.overlay() {
+            data[y][x] = unit;
+        }
         self.output_mut().set_all(data)
     }
 
-    /// Draw only the changes that the [BasicWorld] experienced the previous tick
+    /// Draw only the changes that the [BasicWorld] experienced the previous tick. The
+    /// [SpaceLike::overlay] is drawn again on top, so worlds with one should put the
+    /// cells it left in their delta
     fn draw_delta(&mut self) -> Result<(), String> {
         let changes = self.world().delta().clone();
         let repr = self.reprer();
-        let next = changes.into_iter().map(|(index, cell)| (index, repr(cell)));
+        let overlay = self.overlay();
+        let next = changes
+            .into_iter()
+            .map(|(index, cell)| (index, repr(cell)))
+            .chain(overlay);
         self.output_mut().update(next)
     }
 
@@ -83,21 +108,53 @@ where
     /// If the output field is a buffer, commit the changes to memory
     fn show(&mut self);
 
+    /// Sets a horizontal run of units, the first of which lands on `start`
+    fn set_run(&mut self, (x, y): Index, units: Vec<S>) -> Result<(), String> {
+        for (i, unit) in units.into_iter().enumerate() {
+            self.set_unit((x + i, y), unit, false)?
+        }
+        Ok(())
+    }
+
+    /// Sets a rectangle of units whose top left corner is `origin`, one run per row
+    fn set_rect(&mut self, (x, y): Index, rows: DoubleVec<S>) -> Result<(), String> {
+        for (j, row) in rows.into_iter().enumerate() {
+            self.set_run((x, y + j), row)?
+        }
+        Ok(())
+    }
+
+    /// Presents a whole frame given as a row-major buffer `width` units wide
+    fn present_frame(&mut self, frame: Vec<S>, width: usize) -> Result<(), String> {
+        if width == 0 {
+            return Err(String::from("a frame must be at least one unit wide"));
+        }
+        let mut rows = vec![];
+        let mut units = frame.into_iter().peekable();
+        while units.peek().is_some() {
+            rows.push(units.by_ref().take(width).collect());
+        }
+        self.set_all(rows)
+    }
+
     /// Updates the whole output field with new representations of [BasicCell]s
     fn set_all(&mut self, data: DoubleVec<S>) -> Result<(), String> {
-        for (y, row) in data.into_iter().enumerate() {
-            for (x, unit) in row.into_iter().enumerate() {
-                self.set_unit((x, y), unit, false)?
-            }
-        }
+        self.set_rect((0, 0), data)?;
         self.show();
         Ok(())
     }
 
-    /// Only changes the delta
+    /// Only changes the delta, coalesced into [DirtyRect]s unless it is small
     fn update(&mut self, delta: impl Iterator<Item = (Index, S)>) -> Result<(), String> {
-        for (index, cell) in delta {
-            self.set_unit(index, cell, false)?
+        let delta: Vec<_> = delta.collect();
+        if delta.len() < COALESCE_THRESHOLD {
+            for (index, unit) in delta {
+                self.set_unit(index, unit, false)?
+            }
+        } else {
+            for DirtyRect { origin, units } in coalesce(delta) {
+                self.set_rect(origin, units)?
+            }
         }
         self.show();
         Ok(())
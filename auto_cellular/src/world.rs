@@ -1,10 +1,16 @@
 use crate::{
     cell::CellLike,
     common::{Dimensions, DoubleVec, Index},
+    hex::{self, Topology},
 };
 
 pub trait WorldConfig: Clone {
     fn dimensions(&self) -> &Dimensions;
+
+    /// How the cells are laid out, which spaces follow when drawing and picking them
+    fn topology(&self) -> Topology {
+        Topology::Square
+    }
 }
 
 /// A given [BasicWorld] knows how to go from one state of [BasicCell] to the next on each
@@ -35,11 +41,9 @@ where
     }
 
     fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
-        let cells = (0..config.dimensions().0 * config.dimensions().1)
-            .collect::<Vec<usize>>()
-            .chunks(config.dimensions().0)
-            .into_iter()
-            .map(|chunk| chunk.iter().map(|_| Self::Cell::random(rng)).collect())
+        let Dimensions(w, h) = *config.dimensions();
+        let cells = (0..h)
+            .map(|_| (0..w).map(|_| Self::Cell::random(rng)).collect())
             .collect();
         Self::new(cells, config)
     }
@@ -61,7 +65,9 @@ where
     /// Get the config of the world
     fn config(&self) -> &Self::Config;
 
-    /// Commit the [BasicWorld::changes] to memory
+    /// Commit the [BasicWorld::changes] to memory. Every cell follows the state of the
+    /// grid before the tick; worlds whose cells move or update one after another
+    /// override this (see [crate::common::diff])
     fn tick(&mut self) {
         let changes = self.changes();
         for ((x, y), cell) in changes.iter() {
@@ -70,11 +76,33 @@ where
         *self.delta_mut() = changes;
     }
 
-    /// A click happened at a given [Index]
+    /// A click happened at a given [Index], which moves the cell to its next state
+    /// through [WorldLike::set_cells]
     fn click(&mut self, i @ (x, y): Index) {
-        let c = &mut self.cells_mut()[y][x];
-        *c = c.next_state();
-        *self.delta_mut() = vec![(i, *c)]
+        let next = self.cells()[y][x].next_state();
+        self.set_cells(vec![(i, next)]);
+    }
+
+    /// Overwrite [BasicCell]s from the outside (e.g. when painting). The changes
+    /// become the delta
+    fn set_cells(&mut self, changes: Vec<(Index, Self::Cell)>) {
+        for ((x, y), cell) in changes.iter() {
+            self.cells_mut()[*y][*x] = *cell;
+        }
+        *self.delta_mut() = changes;
+    }
+
+    /// Returns the neighbors of the [BasicCell] at `p` in the [Topology] of the world
+    fn neighbors(&self, p: Index) -> Vec<Index> {
+        match self.config().topology() {
+            Topology::Square => self.moore_neighbors(p),
+            Topology::Hex => self.hex_neighbors(p),
+        }
+    }
+
+    /// Returns the 6 neighbors of the hexagon at `p`, see [Topology::Hex]
+    fn hex_neighbors(&self, p: Index) -> Vec<Index> {
+        hex::neighbors(p, *self.config().dimensions())
     }
 
     /// Returns the Moore Neihgbors for a given [BasicCell] at a given [Index] (x, y)
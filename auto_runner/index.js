@@ -1,7 +1,13 @@
 import init, {
-  tickBriansWorld,
+  frameBriansWorld,
+  togglePause,
+  step,
+  faster,
+  slower,
   getConfig,
-  worldClick,
+  worldPress,
+  worldDrag,
+  worldRelease,
   firstDrawBrains,
   worldReload,
   blankWorld,
@@ -19,11 +25,15 @@ ctx.canvas.height = config.height;
 
 console.log(ctx.canvas.width, ctx.canvas.height);
 
-let isPaused = false;
-
 window.addEventListener("keydown", (event) => {
   if (event.key === " ") {
-    isPaused = !isPaused;
+    togglePause();
+  } else if (event.key === "n") {
+    step();
+  } else if (event.key === "l") {
+    faster();
+  } else if (event.key === "h") {
+    slower();
   } else if (event.key === "r") {
     worldReload();
   } else if (event.key === "b") {
@@ -31,22 +41,34 @@ window.addEventListener("keydown", (event) => {
   }
 });
 
-canvas.addEventListener("click", (event) => {
+function cellAt(event) {
   const x = event.clientX - canvas.offsetLeft;
   const y = event.clientY - canvas.offsetTop;
 
-  const dx = x / config.pixel_size,
-    dy = y / config.pixel_size;
+  return [Math.floor(x / config.pixel_size), Math.floor(y / config.pixel_size)];
+}
+
+canvas.addEventListener("contextmenu", (event) => event.preventDefault());
 
-  worldClick(dx, dy);
+canvas.addEventListener("mousedown", (event) => {
+  const [dx, dy] = cellAt(event);
+  worldPress(dx, dy, event.button, event.shiftKey, event.ctrlKey, event.altKey);
 });
 
+canvas.addEventListener("mousemove", (event) => {
+  if (event.buttons === 0) return;
+  const [dx, dy] = cellAt(event);
+  worldDrag(dx, dy);
+});
+
+window.addEventListener("mouseup", () => worldRelease());
+
 firstDrawBrains();
 
-function render() {
-  if (!isPaused) tickBriansWorld();
+function render(now) {
+  frameBriansWorld(now);
 
-  setTimeout(() => render(), 100);
+  requestAnimationFrame(render);
 }
 
-render();
+requestAnimationFrame(render);
@@ -1,13 +1,16 @@
-use auto_cellular::{common::Dimensions, world::WorldLike};
+use auto_cellular::{clock::ClockConfig, common::Dimensions, hex::Layout, world::WorldLike};
 use auto_spaces::sdl2_canvas::{self, Config};
 use auto_worlds::WorldKind;
 use sdl2::pixels::Color;
 
+use crate::options::WorldOptions;
+
 pub fn run(
     world: WorldKind,
+    options: &WorldOptions,
     window_dimensions: Dimensions,
     pixel_size: usize,
-    update_millis: usize,
+    clock: ClockConfig,
 ) -> Result<(), String> {
     let world_dimensions = Dimensions(
         window_dimensions.0 / pixel_size,
@@ -15,7 +18,7 @@ pub fn run(
     );
 
     let mut rng = rand::thread_rng();
-    let config = Config::new(window_dimensions, pixel_size, update_millis as u64);
+    let config = Config::new(window_dimensions, pixel_size, clock);
 
     match world {
         WorldKind::GameOfLife => {
@@ -41,40 +44,540 @@ pub fn run(
                 Cell::Off => Color::RGB(35, 25, 85),
             })?;
         }
-        WorldKind::LangtonsAnt => {
-            use auto_worlds::langtonsant::{
-                cell::{Cell, CellType, Color as CellColor},
-                world::{WConfig, World},
+        WorldKind::LifeLike => {
+            use auto_worlds::{gameoflife::Cell, isotropic, lifelike};
+            let repr = |c| match c {
+                Cell::Alive => Color::RGB(248, 90, 202),
+                Cell::Dead => Color::RGB(16, 7, 32),
             };
-            use CellType::*;
+            // rules that are not totalistic are run by the isotropic world
+            let rule = options.rule_or("B3/S23");
+            match rule.parse::<lifelike::Rule>() {
+                Ok(rule) => {
+                    let world_config = lifelike::WConfig {
+                        dimensions: world_dimensions,
+                        rule,
+                    };
+                    let world = lifelike::World::new_random(&mut rng, world_config);
+                    sdl2_canvas::run(config, world, &format!("Life-like {}", rule), repr)?;
+                }
+                Err(_) => {
+                    let rule: isotropic::Rule = rule.parse()?;
+                    let world_config = isotropic::WConfig {
+                        dimensions: world_dimensions,
+                        rule,
+                    };
+                    let world = isotropic::World::new_random(&mut rng, world_config);
+                    sdl2_canvas::run(config, world, &format!("Isotropic {}", rule), repr)?;
+                }
+            }
+        }
+        WorldKind::Generations => {
+            use auto_worlds::generations::{Rule, WConfig, World};
+            let rule: Rule = options.rule_or("B2/S/C3").parse()?;
             let world_config = WConfig {
                 dimensions: world_dimensions,
+                rule,
             };
-            let world =
-                World::random_with_pattern_of(&mut rng, world_config, vec![CCW, CCW, CW, CW]);
-            println!("{:?}", world.pattern);
-
-            sdl2_canvas::run(config, world, "Langton's Ant", |c| {
-                let colors = [
-                    Color::RGB(16, 7, 32),
-                    Color::RGB(68, 45, 124),
-                    Color::RGB(99, 65, 180),
-                    Color::RGB(218, 250, 139),
-                    Color::RGB(230, 230, 250),
-                    Color::RGB(32, 178, 170),
-                    Color::RGB(152, 251, 152),
-                    Color::RGB(50, 205, 50),
-                    Color::RGB(0, 255, 0),
-                    Color::WHITE,
-                ];
-
-                match c {
-                    Cell::Ant(_, _) => Color::RED,
-                    Cell::Color(CellColor { value, .. }) => colors[value],
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("Generations {}", rule), |c| {
+                if c.is_alive() {
+                    Color::RGB(255, 229, 180)
+                } else {
+                    gradient(Color::RGB(35, 25, 85), Color::RGB(31, 70, 144), c.level())
                 }
             })?;
         }
+        WorldKind::LargerThanLife => {
+            use auto_worlds::largerthanlife::{Rule, WConfig, World};
+            let rule: Rule = options.rule_or("bosco").parse()?;
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule: rule.clone(),
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("Larger than Life {}", rule), |c| {
+                if c.is_alive() {
+                    Color::RGB(248, 90, 202)
+                } else {
+                    gradient(Color::RGB(16, 7, 32), Color::RGB(99, 65, 180), c.level())
+                }
+            })?;
+        }
+        WorldKind::Wolfram => {
+            use auto_worlds::wolfram::{Rule, WConfig, World};
+            let rule: Rule = options.rule_or("30").parse()?;
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule: rule.clone(),
+                seed: options.param("seed", Default::default())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("Wolfram {}", rule), |c| {
+                gradient(Color::WHITE, Color::RGB(16, 7, 32), c.level())
+            })?;
+        }
+        WorldKind::WireWorld => {
+            use auto_worlds::wireworld::{Cell, WConfig, World};
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+            };
+            let world = match &options.preset {
+                Some(name) => World::from_preset(name, world_config)?,
+                None => World::new_random(&mut rng, world_config),
+            };
+            sdl2_canvas::run(config, world, "WireWorld", |c| match c {
+                Cell::Empty => Color::RGB(16, 7, 32),
+                Cell::Conductor => Color::RGB(230, 160, 20),
+                Cell::Head => Color::RGB(40, 120, 255),
+                Cell::Tail => Color::RGB(230, 40, 40),
+            })?;
+        }
+        WorldKind::Cyclic => {
+            use auto_worlds::cyclic::{Rule, WConfig, World};
+            let rule: Rule = options.rule_or("313").parse()?;
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("Cyclic {}", rule), |c| {
+                hue(c.state() as f64 / c.states() as f64)
+            })?;
+        }
+        WorldKind::GreenbergHastings => {
+            use auto_worlds::greenberghastings::{rule, WConfig, World};
+            let rule = rule(options.rule_or("classic"))?;
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(
+                config,
+                world,
+                &format!("Greenberg-Hastings {}", rule),
+                |c| match c.state() {
+                    0 => Color::RGB(16, 7, 32),
+                    1 => Color::RGB(255, 250, 205),
+                    _ => gradient(Color::RGB(40, 20, 90), Color::RGB(230, 90, 40), c.level()),
+                },
+            )?;
+        }
+        WorldKind::HexLife => {
+            use auto_worlds::hexlife::{rule, Cell, Seed, WConfig, World};
+            let rule = rule(options.rule_or("hexlife"))?;
+            let world_config = WConfig {
+                dimensions: Layout::new(pixel_size as f64).fit(window_dimensions),
+                rule,
+                seed: options.param("seed", Seed::Random)?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("Hex Life {}", rule), |c| match c {
+                Cell::Alive => Color::RGB(248, 90, 202),
+                Cell::Dead => Color::RGB(16, 7, 32),
+            })?;
+        }
+        WorldKind::RuleTable => {
+            use auto_worlds::ruletable::{Rule, WConfig, World};
+            let rule: Rule = match options.read_rule_file()? {
+                Some(contents) => contents.parse()?,
+                None => Rule::default(),
+            };
+            let title = rule.name.clone();
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &title, |c| {
+                let [r, g, b] = c.color();
+                Color::RGB(r, g, b)
+            })?;
+        }
+        WorldKind::Margolus => {
+            use auto_worlds::margolus::{Cell, Rule, WConfig, World};
+            let rule: Rule = options.rule_or("bbm").parse()?;
+            let world_config = WConfig {
+                // blocks only tile an even number of rows and columns
+                dimensions: Dimensions(world_dimensions.0 & !1, world_dimensions.1 & !1),
+                rule,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("Margolus {}", rule), |c| match c {
+                Cell::Alive => Color::RGB(248, 90, 202),
+                Cell::Dead => Color::RGB(16, 7, 32),
+            })?;
+        }
+        WorldKind::FallingSand => {
+            use auto_worlds::fallingsand::{Cell, WConfig, World};
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            let palette = [
+                ("sand", Cell::Sand),
+                ("water", Cell::Water),
+                ("stone", Cell::Stone),
+                ("fire", Cell::Fire),
+                ("smoke", Cell::Smoke),
+                ("oil", Cell::Oil),
+            ];
+            sdl2_canvas::run_with_palette(
+                config,
+                world,
+                "Falling Sand",
+                |c| match c {
+                    Cell::Empty => Color::RGB(16, 7, 32),
+                    Cell::Sand => Color::RGB(220, 190, 110),
+                    Cell::Water => Color::RGB(40, 110, 230),
+                    Cell::Stone => Color::RGB(110, 110, 120),
+                    Cell::Fire => Color::RGB(255, 110, 20),
+                    Cell::Smoke => Color::RGB(70, 65, 80),
+                    Cell::Oil => Color::RGB(90, 50, 30),
+                },
+                &palette,
+            )?;
+        }
+        WorldKind::ForestFire => {
+            use auto_worlds::{
+                forestfire::{Cell, WConfig, World},
+                neighborhood::Neighborhood,
+            };
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                growth: options.probability("growth", 0.01)?,
+                lightning: options.probability("lightning", 0.00001)?,
+                neighborhood: Neighborhood::VonNeumann,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            let world = sdl2_canvas::run_with_tweaks(
+                config,
+                world,
+                "Forest Fire",
+                |c| match c {
+                    Cell::Empty => Color::RGB(16, 7, 32),
+                    Cell::Tree => Color::RGB(40, 140, 60),
+                    Cell::Burning => Color::RGB(255, 110, 20),
+                },
+                &[],
+                |w| w.census().summary::<Cell>(),
+            )?;
+            options.export(|| world.census().to_csv::<Cell>())?;
+        }
+        WorldKind::Sir => {
+            use auto_worlds::{
+                neighborhood::Neighborhood,
+                sir::{Cell, WConfig, World},
+            };
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                infection: options.probability("infection", 0.2)?,
+                recovery: options.probability("recovery", 0.1)?,
+                immunity_loss: options.probability("immunity_loss", 0.005)?,
+                neighborhood: Neighborhood::Moore,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            let world = sdl2_canvas::run_with_tweaks(
+                config,
+                world,
+                "SIR Epidemic",
+                |c| match c {
+                    Cell::Susceptible => Color::RGB(35, 25, 85),
+                    Cell::Infected => Color::RGB(230, 40, 40),
+                    Cell::Recovered => Color::RGB(120, 200, 140),
+                },
+                &[],
+                |w| w.census().summary::<Cell>(),
+            )?;
+            options.export(|| world.census().to_csv::<Cell>())?;
+        }
+        WorldKind::Sandpile => {
+            use auto_worlds::sandpile::{identity, DropSite, WConfig, World};
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                drop: options.param("drop", DropSite::Center)?,
+                relax: options.param("relax", Default::default())?,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = match (options.preset.as_deref(), world_config.drop) {
+                (Some("identity"), _) => World::new(identity(world_dimensions), world_config),
+                (Some(name), _) => return Err(format!("unknown preset `{}`", name)),
+                (None, DropSite::Random) => World::new_random(&mut rng, world_config),
+                (None, _) => World::new_blank(world_config),
+            };
+            let world = sdl2_canvas::run_with_tweaks(
+                config,
+                world,
+                "Sandpile",
+                |c| match c.grains {
+                    0 => Color::RGB(16, 7, 32),
+                    1 => Color::RGB(40, 60, 160),
+                    2 => Color::RGB(60, 170, 200),
+                    3 => Color::RGB(250, 210, 90),
+                    _ => Color::WHITE,
+                },
+                &[],
+                |w| match w.avalanches().last() {
+                    Some(last) => format!(
+                        "{} avalanches, the last of size {}",
+                        w.avalanches().len(),
+                        last.size
+                    ),
+                    None => String::from("no avalanches yet"),
+                },
+            )?;
+            options.export(|| world.sizes_csv())?;
+        }
+        WorldKind::Lenia => {
+            use auto_worlds::lenia::{Rule, WConfig, World};
+            let rule: Rule = options.rule_or("orbium").parse()?;
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule: rule.clone(),
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("Lenia {}", rule), |c| {
+                colormap(c.value as f64)
+            })?;
+        }
+        WorldKind::SmoothLife => {
+            use auto_worlds::smoothlife::{Rule, WConfig, World};
+            let rule: Rule = match &options.rule {
+                Some(rule) => rule.parse()?,
+                None => Rule::default(),
+            };
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule: rule.clone(),
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("SmoothLife {}", rule), |c| {
+                colormap(c.value as f64)
+            })?;
+        }
+        WorldKind::GrayScott => {
+            use auto_worlds::grayscott::{Rule, WConfig, World};
+            let rule: Rule = options.rule_or("mitosis").parse()?;
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule: rule.clone(),
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run(config, world, &format!("Gray-Scott {}", rule), |c| {
+                colormap(1.0 - (c.a - c.b) as f64)
+            })?;
+        }
+        WorldKind::WaTor => {
+            use auto_worlds::wator::{Cell, WConfig, World};
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                fish_breed: options.param("fish_breed", 3)?,
+                shark_breed: options.param("shark_breed", 10)?,
+                starve: options.param("starve", 3)?,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            let world = sdl2_canvas::run_with_tweaks(
+                config,
+                world,
+                "Wa-Tor",
+                |c| match c {
+                    Cell::Water => Color::RGB(16, 7, 32),
+                    Cell::Fish { .. } => Color::RGB(60, 170, 200),
+                    Cell::Shark { .. } => Color::RGB(230, 40, 40),
+                },
+                &[],
+                |w| match w.populations().last() {
+                    Some((fish, sharks)) => format!("{} fish, {} sharks", fish, sharks),
+                    None => String::new(),
+                },
+            )?;
+            options.export(|| world.phase_csv())?;
+        }
+        WorldKind::Ising => {
+            use auto_worlds::ising::{Cell, WConfig, World, CRITICAL_TEMPERATURE};
+            use sdl2::keyboard::Keycode;
+            let world_config = WConfig {
+                // the checkerboard only alternates across the edges with even dimensions
+                dimensions: Dimensions(world_dimensions.0 & !1, world_dimensions.1 & !1),
+                temperature: options.param("temperature", CRITICAL_TEMPERATURE)?,
+                coupling: options.param("coupling", 1.0)?,
+                field: options.param("field", 0.0)?,
+                dynamics: options.param("dynamics", Default::default())?,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run_with_tweaks(
+                config,
+                world,
+                "Ising",
+                |c| match c {
+                    Cell::Up => Color::RGB(248, 90, 202),
+                    Cell::Down => Color::RGB(16, 7, 32),
+                },
+                &[
+                    (Keycode::Up, |w| w.set_temperature(w.temperature() + 0.1)),
+                    (Keycode::Down, |w| w.set_temperature(w.temperature() - 0.1)),
+                ],
+                |w| {
+                    let last = w.measurements().last().copied().unwrap_or_default();
+                    format!(
+                        "T={:.2} (up/down), m={:.3}, E={:.3}",
+                        w.temperature(),
+                        last.magnetization,
+                        last.energy
+                    )
+                },
+            )?;
+        }
+        WorldKind::Schelling => {
+            use auto_worlds::{
+                neighborhood::Neighborhood,
+                schelling::{Cell, WConfig, World, MAX_GROUPS},
+            };
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                groups: options.param("groups", 2)?,
+                vacancy: options.probability("vacancy", 0.1)?,
+                threshold: options.probability("threshold", 0.3)?,
+                neighborhood: Neighborhood::Moore,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run_with_tweaks(
+                config,
+                world,
+                "Schelling",
+                |c| match c {
+                    Cell::Vacant => Color::RGB(16, 7, 32),
+                    Cell::Agent(group) => hue(group as f64 / MAX_GROUPS as f64),
+                },
+                &[],
+                |w| {
+                    let last = w.segregation().last().copied().unwrap_or_default();
+                    format!(
+                        "similarity={:.3}, unhappy={:.3}",
+                        last.similarity, last.unhappy
+                    )
+                },
+            )?;
+        }
+        WorldKind::Voter => {
+            use auto_worlds::{
+                neighborhood::Neighborhood,
+                voter::{Cell, Rule, WConfig, World},
+            };
+            let rule: Rule = options.rule_or("voter").parse()?;
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule,
+                noise: options.probability("noise", 0.0)?,
+                neighborhood: Neighborhood::Moore,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+            sdl2_canvas::run_with_tweaks(
+                config,
+                world,
+                &format!("Voter {}", rule),
+                |c| match c {
+                    Cell::Yes => Color::RGB(248, 90, 202),
+                    Cell::No => Color::RGB(16, 7, 32),
+                },
+                &[],
+                |w| {
+                    let last = w.consensus().last().copied().unwrap_or_default();
+                    format!(
+                        "majority={:.3}, interfaces={:.3}",
+                        last.majority, last.interfaces
+                    )
+                },
+            )?;
+        }
+        WorldKind::Turmite => {
+            use auto_worlds::turmite::{Rule, WConfig, World};
+            let rule: Rule = options.rule_or("langton").parse()?;
+            let world_config = WConfig {
+                dimensions: world_dimensions,
+                rule: rule.clone(),
+                collision: options.param("collision", Default::default())?,
+            };
+            let world = World::random_with_ants(
+                &mut rng,
+                world_config,
+                options.param("ants", 1)?,
+                &options.list::<Rule>("rules")?,
+            );
+
+            sdl2_canvas::run_with_overlay(
+                config,
+                world,
+                &format!("Turmite {}", rule),
+                |c| {
+                    let colors = [
+                        Color::RGB(16, 7, 32),
+                        Color::RGB(68, 45, 124),
+                        Color::RGB(99, 65, 180),
+                        Color::RGB(218, 250, 139),
+                        Color::RGB(230, 230, 250),
+                        Color::RGB(32, 178, 170),
+                        Color::RGB(152, 251, 152),
+                        Color::RGB(50, 205, 50),
+                        Color::RGB(0, 255, 0),
+                        Color::WHITE,
+                    ];
+                    colors[c.color as usize % colors.len()]
+                },
+                |w| {
+                    w.ants()
+                        .iter()
+                        .map(|ant| (ant.position, Color::RED))
+                        .collect()
+                },
+            )?;
+        }
     }
 
     Ok(())
 }
+
+/// A fully saturated color `t` of the way around the color wheel
+fn hue(t: f64) -> Color {
+    let h = t.rem_euclid(1.0) * 6.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as u8 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    let channel = |v: f64| (v * 255.0).round() as u8;
+    Color::RGB(channel(r), channel(g), channel(b))
+}
+
+/// Mixes `from` and `to`, `t` going from 0 (all `from`) to 1 (all `to`)
+fn gradient(from: Color, to: Color, t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    Color::RGB(mix(from.r, to.r), mix(from.g, to.g), mix(from.b, to.b))
+}
+
+/// A smooth ramp from dark blue through teal to yellow, for levels between 0 and 1
+fn colormap(t: f64) -> Color {
+    let stops = [
+        Color::RGB(16, 7, 32),
+        Color::RGB(49, 54, 149),
+        Color::RGB(33, 145, 140),
+        Color::RGB(94, 201, 98),
+        Color::RGB(253, 231, 37),
+    ];
+    let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+    let i = (t as usize).min(stops.len() - 2);
+    gradient(stops[i], stops[i + 1], t - i as f64)
+}
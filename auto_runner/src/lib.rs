@@ -1,5 +1,6 @@
 #[cfg(feature = "sdl2")]
 pub mod gui;
+pub mod options;
 #[cfg(feature = "cursive")]
 pub mod terminal;
 #[cfg(feature = "wasm")]
@@ -1,9 +1,13 @@
+use std::path::PathBuf;
+
 use auto_spaces::SpaceKind;
 use auto_worlds::WorldKind;
 use clap::Parser;
+use options::{parse_param, WorldOptions};
 
 #[cfg(feature = "sdl2")]
 pub mod gui;
+pub mod options;
 #[cfg(feature = "cursive")]
 pub mod terminal;
 #[cfg(feature = "wasm")]
@@ -25,37 +29,67 @@ struct Args {
     #[clap(short, long, value_parser, default_value_t = 20)]
     cell_size: usize,
 
-    /// Time to wait between each tick
-    #[clap(short, long, value_parser, default_value_t = 100)]
-    update_millis: usize,
+    /// Ticks per second to aim for
+    #[clap(short, long, value_parser, default_value_t = 10.0)]
+    tps: f64,
+
+    /// Frames per second to render at, independently of the ticks
+    #[clap(long, value_parser, default_value_t = 60.0)]
+    fps: f64,
 
     /// The type of [Worlds] to simulate
     #[clap(short, long)]
     world: WorldKind,
 
+    /// Rulestring or preset name for rule-driven worlds (e.g. `B36/S23` or `highlife`)
+    #[clap(short, long)]
+    rule: Option<String>,
+
+    /// Golly `.rule` file for the rule table world
+    #[clap(long)]
+    rule_file: Option<PathBuf>,
+
+    /// Starting pattern for worlds that ship some (e.g. `xor` for WireWorld)
+    #[clap(long)]
+    preset: Option<String>,
+
+    /// World specific parameters as `key=value`, e.g. `seed=random`, or `export=path` to
+    /// save what the world measured when the window is closed
+    #[clap(short, long = "param", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
     /// the type of [Space] to use as canvas
     #[clap(short, long)]
     frontend: SpaceKind,
 }
 
 fn main() -> Result<(), String> {
-    use auto_cellular::common::Dimensions;
+    use auto_cellular::{clock::ClockConfig, common::Dimensions};
 
     let args = Args::parse();
+    let clock = ClockConfig::new(args.tps, args.fps);
+    let options = WorldOptions {
+        rule: args.rule,
+        rule_file: args.rule_file,
+        preset: args.preset,
+        params: args.params,
+    };
 
     match args.frontend {
         #[cfg(feature = "cursive")]
         SpaceKind::Cursive => terminal::run(
             args.world,
+            &options,
             Dimensions(args.width, args.height),
-            args.update_millis,
+            clock,
         ),
         #[cfg(feature = "sdl2")]
         SpaceKind::Sdl2 => gui::run(
             args.world,
+            &options,
             Dimensions(args.width, args.height),
             args.cell_size,
-            args.update_millis,
+            clock,
         ),
     }
 }
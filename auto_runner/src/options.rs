@@ -0,0 +1,82 @@
+use std::{fmt::Display, fs, path::PathBuf, str::FromStr};
+
+/// Settings for building a world beyond its [auto_worlds::WorldKind]
+#[derive(Clone, Debug, Default)]
+pub struct WorldOptions {
+    /// Rulestring (or preset name) of rule-driven worlds
+    pub rule: Option<String>,
+    /// Path of a rule file, for worlds that load their rule from one
+    pub rule_file: Option<PathBuf>,
+    /// Name of a starting pattern for worlds that ship some
+    pub preset: Option<String>,
+    /// World specific `key=value` parameters
+    pub params: Vec<(String, String)>,
+}
+
+impl WorldOptions {
+    /// The rulestring, or `default` when none was given
+    pub fn rule_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.rule.as_deref().unwrap_or(default)
+    }
+
+    /// The contents of the rule file, if one was given
+    pub fn read_rule_file(&self) -> Result<Option<String>, String> {
+        self.rule_file
+            .as_ref()
+            .map(|path| {
+                fs::read_to_string(path)
+                    .map_err(|e| format!("could not read `{}`: {}", path.display(), e))
+            })
+            .transpose()
+    }
+
+    /// The last value given for `key`, or `default` when there is none
+    pub fn param<T: FromStr>(&self, key: &str, default: T) -> Result<T, String> {
+        match self.params.iter().rev().find(|(k, _)| k == key) {
+            Some((_, value)) => value
+                .parse()
+                .map_err(|_| format!("`{}` is not a valid value for `{}`", value, key)),
+            None => Ok(default),
+        }
+    }
+
+    /// Writes what `contents` gives to the path given as `export`, if one was given
+    pub fn export(&self, contents: impl FnOnce() -> String) -> Result<(), String> {
+        match self.params.iter().rev().find(|(k, _)| k == "export") {
+            Some((_, path)) => fs::write(path, contents())
+                .map_err(|e| format!("could not write `{}`: {}", path, e)),
+            None => Ok(()),
+        }
+    }
+
+    /// Like [WorldOptions::param], for a probability between 0 and 1
+    pub fn probability(&self, key: &str, default: f64) -> Result<f64, String> {
+        let p = self.param(key, default)?;
+        if (0.0..=1.0).contains(&p) {
+            Ok(p)
+        } else {
+            Err(format!("`{}` should be between 0 and 1, not {}", key, p))
+        }
+    }
+
+    /// The `;` separated values given for `key`, or none when there are none
+    pub fn list<T: FromStr>(&self, key: &str) -> Result<Vec<T>, String>
+    where
+        T::Err: Display,
+    {
+        match self.params.iter().rev().find(|(k, _)| k == key) {
+            Some((_, values)) => values
+                .split(';')
+                .map(|value| value.parse().map_err(|e| format!("in `{}`: {}", key, e)))
+                .collect(),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+/// Splits a `key=value` pair
+pub fn parse_param(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .ok_or_else(|| format!("`{}` should look like `key=value`", s))
+}
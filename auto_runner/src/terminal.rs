@@ -1,10 +1,17 @@
-use auto_cellular::{common::Dimensions, world::WorldLike};
+use auto_cellular::{clock::ClockConfig, common::Dimensions, world::WorldLike};
 
 use auto_spaces::cursive_canvas;
 
 use auto_worlds::WorldKind;
 
-pub fn run(world: WorldKind, dimensions: Dimensions, update_millis: usize) -> Result<(), String> {
+use crate::options::WorldOptions;
+
+pub fn run(
+    world: WorldKind,
+    options: &WorldOptions,
+    dimensions: Dimensions,
+    clock: ClockConfig,
+) -> Result<(), String> {
     let mut rng = rand::thread_rng();
     match world {
         WorldKind::GameOfLife => {
@@ -17,7 +24,7 @@ pub fn run(world: WorldKind, dimensions: Dimensions, update_millis: usize) -> Re
                     Cell::Alive => '#',
                     Cell::Dead => ' ',
                 },
-                update_millis,
+                clock,
             )?;
         }
         WorldKind::BriansBrain => {
@@ -30,44 +37,465 @@ pub fn run(world: WorldKind, dimensions: Dimensions, update_millis: usize) -> Re
                     Cell::Dying => '*',
                     Cell::Off => ' ',
                 },
-                update_millis,
+                clock,
             )?;
         }
-        WorldKind::LangtonsAnt => {
-            use auto_worlds::langtonsant::{
-                cell::{Cell, CellType::*, Color, Direction},
-                world::{WConfig, World},
+        WorldKind::LifeLike => {
+            use auto_worlds::{gameoflife::Cell, isotropic, lifelike};
+            let repr = |c| match c {
+                Cell::Alive => '#',
+                Cell::Dead => ' ',
             };
+            // rules that are not totalistic are run by the isotropic world
+            let rule = options.rule_or("B3/S23");
+            match rule.parse() {
+                Ok(rule) => {
+                    let config = lifelike::WConfig { dimensions, rule };
+                    let world = lifelike::World::new_random(&mut rng, config);
+                    cursive_canvas::run(world, repr, clock)?;
+                }
+                Err(_) => {
+                    let rule = rule.parse()?;
+                    let config = isotropic::WConfig { dimensions, rule };
+                    let world = isotropic::World::new_random(&mut rng, config);
+                    cursive_canvas::run(world, repr, clock)?;
+                }
+            }
+        }
+        WorldKind::Generations => {
+            use auto_worlds::generations::{WConfig, World};
+            let rule = options.rule_or("B2/S/C3").parse()?;
+            let world = World::new_random(&mut rng, WConfig { dimensions, rule });
 
-            let world = World::random_with_pattern_of(
+            cursive_canvas::run(
+                world,
+                |c| if c.is_alive() { 'O' } else { shade(c.level()) },
+                clock,
+            )?;
+        }
+        WorldKind::LargerThanLife => {
+            use auto_worlds::largerthanlife::{WConfig, World};
+            let rule = options.rule_or("bosco").parse()?;
+            let world = World::new_random(&mut rng, WConfig { dimensions, rule });
+
+            cursive_canvas::run(
+                world,
+                |c| if c.is_alive() { '#' } else { shade(c.level()) },
+                clock,
+            )?;
+        }
+        WorldKind::Wolfram => {
+            use auto_worlds::wolfram::{WConfig, World};
+            let rule = options.rule_or("30").parse()?;
+            let seed = options.param("seed", Default::default())?;
+            let world = World::new_random(
                 &mut rng,
-                WConfig { dimensions },
-                //vec![CCW, CW, CW, CW, CW, CW, CCW, CCW, CW],
-                vec![CCW, CCW, CW, CW],
+                WConfig {
+                    dimensions,
+                    rule,
+                    seed,
+                },
             );
-            let world = world.blank();
-            println!("{:?}", world.pattern);
+
+            cursive_canvas::run(world, |c| shade(c.level()), clock)?;
+        }
+        WorldKind::WireWorld => {
+            use auto_worlds::wireworld::{Cell, WConfig, World};
+            let world = match &options.preset {
+                Some(name) => World::from_preset(name, WConfig { dimensions })?,
+                None => World::new_random(&mut rng, WConfig { dimensions }),
+            };
+
+            cursive_canvas::run(
+                world,
+                |c| match c {
+                    Cell::Empty => ' ',
+                    Cell::Conductor => '#',
+                    Cell::Head => '@',
+                    Cell::Tail => '~',
+                },
+                clock,
+            )?;
+        }
+        WorldKind::Cyclic => {
+            use auto_worlds::cyclic::{WConfig, World};
+            let rule = options.rule_or("313").parse()?;
+            let world = World::new_random(&mut rng, WConfig { dimensions, rule });
+
+            cursive_canvas::run(
+                world,
+                |c| shade(c.state() as f64 / (c.states() - 1) as f64),
+                clock,
+            )?;
+        }
+        WorldKind::GreenbergHastings => {
+            use auto_worlds::greenberghastings::{rule, WConfig, World};
+            let rule = rule(options.rule_or("classic"))?;
+            let world = World::new_random(&mut rng, WConfig { dimensions, rule });
+
+            cursive_canvas::run(world, |c| shade(c.level()), clock)?;
+        }
+        WorldKind::HexLife => {
+            use auto_worlds::hexlife::{rule, Cell, Seed, WConfig, World};
+            let world_config = WConfig {
+                // every hexagon is two characters wide and odd rows start one over, and
+                // the rows come in pairs so the grid wraps around
+                dimensions: Dimensions(dimensions.0.saturating_sub(1) / 2, dimensions.1 & !1),
+                rule: rule(options.rule_or("hexlife"))?,
+                seed: options.param("seed", Seed::Random)?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+
+            cursive_canvas::run(
+                world,
+                |c| match c {
+                    Cell::Alive => '#',
+                    Cell::Dead => ' ',
+                },
+                clock,
+            )?;
+        }
+        WorldKind::RuleTable => {
+            use auto_worlds::ruletable::{Rule, WConfig, World};
+            let rule: Rule = match options.read_rule_file()? {
+                Some(contents) => contents.parse()?,
+                None => Rule::default(),
+            };
+            let world = World::new_random(&mut rng, WConfig { dimensions, rule });
+
+            cursive_canvas::run(
+                world,
+                |c| match c.state() {
+                    0 => ' ',
+                    s => char::from_digit(s as u32, 36).unwrap_or('#'),
+                },
+                clock,
+            )?;
+        }
+        WorldKind::Margolus => {
+            use auto_worlds::margolus::{Cell, WConfig, World};
+            let world_config = WConfig {
+                // blocks only tile an even number of rows and columns
+                dimensions: Dimensions(dimensions.0 & !1, dimensions.1 & !1),
+                rule: options.rule_or("bbm").parse()?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+
+            cursive_canvas::run(
+                world,
+                |c| match c {
+                    Cell::Alive => '#',
+                    Cell::Dead => ' ',
+                },
+                clock,
+            )?;
+        }
+        WorldKind::FallingSand => {
+            use auto_worlds::fallingsand::{Cell, WConfig, World};
+            let world_config = WConfig {
+                dimensions,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
 
             cursive_canvas::run(
+                world,
+                |c| match c {
+                    Cell::Empty => ' ',
+                    Cell::Sand => '.',
+                    Cell::Water => '~',
+                    Cell::Stone => '#',
+                    Cell::Fire => '^',
+                    Cell::Smoke => '"',
+                    Cell::Oil => 'o',
+                },
+                clock,
+            )?;
+        }
+        WorldKind::ForestFire => {
+            use auto_worlds::{
+                forestfire::{Cell, WConfig, World},
+                neighborhood::Neighborhood,
+            };
+            let world_config = WConfig {
+                dimensions,
+                growth: options.probability("growth", 0.01)?,
+                lightning: options.probability("lightning", 0.00001)?,
+                neighborhood: Neighborhood::VonNeumann,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+
+            let world = cursive_canvas::run_with_tweaks(
+                world,
+                |c| match c {
+                    Cell::Empty => ' ',
+                    Cell::Tree => '^',
+                    Cell::Burning => '*',
+                },
+                &[],
+                |w| w.census().summary::<Cell>(),
+                clock,
+            )?;
+            options.export(|| world.census().to_csv::<Cell>())?;
+        }
+        WorldKind::Sir => {
+            use auto_worlds::{
+                neighborhood::Neighborhood,
+                sir::{Cell, WConfig, World},
+            };
+            let world_config = WConfig {
+                dimensions,
+                infection: options.probability("infection", 0.2)?,
+                recovery: options.probability("recovery", 0.1)?,
+                immunity_loss: options.probability("immunity_loss", 0.005)?,
+                neighborhood: Neighborhood::Moore,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+
+            let world = cursive_canvas::run_with_tweaks(
+                world,
+                |c| match c {
+                    Cell::Susceptible => '.',
+                    Cell::Infected => '@',
+                    Cell::Recovered => ' ',
+                },
+                &[],
+                |w| w.census().summary::<Cell>(),
+                clock,
+            )?;
+            options.export(|| world.census().to_csv::<Cell>())?;
+        }
+        WorldKind::Sandpile => {
+            use auto_worlds::sandpile::{identity, DropSite, WConfig, World};
+            let world_config = WConfig {
+                dimensions,
+                drop: options.param("drop", DropSite::Center)?,
+                relax: options.param("relax", Default::default())?,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = match (options.preset.as_deref(), world_config.drop) {
+                (Some("identity"), _) => World::new(identity(dimensions), world_config),
+                (Some(name), _) => return Err(format!("unknown preset `{}`", name)),
+                (None, DropSite::Random) => World::new_random(&mut rng, world_config),
+                (None, _) => World::new_blank(world_config),
+            };
+
+            let world = cursive_canvas::run_with_tweaks(
+                world,
+                |c| {
+                    [' ', '.', ':', '#']
+                        .get(c.grains as usize)
+                        .copied()
+                        .unwrap_or('@')
+                },
+                &[],
+                |w| match w.avalanches().last() {
+                    Some(last) => format!(
+                        "{} avalanches, the last of size {}",
+                        w.avalanches().len(),
+                        last.size
+                    ),
+                    None => String::from("no avalanches yet"),
+                },
+                clock,
+            )?;
+            options.export(|| world.sizes_csv())?;
+        }
+        WorldKind::Lenia => {
+            use auto_worlds::lenia::{WConfig, World};
+            let rule = options.rule_or("orbium").parse()?;
+            let world = World::new_random(&mut rng, WConfig { dimensions, rule });
+
+            cursive_canvas::run(world, |c| shade(c.value as f64), clock)?;
+        }
+        WorldKind::SmoothLife => {
+            use auto_worlds::smoothlife::{Rule, WConfig, World};
+            let rule = match &options.rule {
+                Some(rule) => rule.parse()?,
+                None => Rule::default(),
+            };
+            let world = World::new_random(&mut rng, WConfig { dimensions, rule });
+
+            cursive_canvas::run(world, |c| shade(c.value as f64), clock)?;
+        }
+        WorldKind::GrayScott => {
+            use auto_worlds::grayscott::{WConfig, World};
+            let rule = options.rule_or("mitosis").parse()?;
+            let world = World::new_random(&mut rng, WConfig { dimensions, rule });
+
+            cursive_canvas::run(world, |c| shade(1.0 - (c.a - c.b) as f64), clock)?;
+        }
+        WorldKind::WaTor => {
+            use auto_worlds::wator::{Cell, WConfig, World};
+            let world_config = WConfig {
+                dimensions,
+                fish_breed: options.param("fish_breed", 3)?,
+                shark_breed: options.param("shark_breed", 10)?,
+                starve: options.param("starve", 3)?,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+
+            let world = cursive_canvas::run_with_tweaks(
+                world,
+                |c| match c {
+                    Cell::Water => ' ',
+                    Cell::Fish { .. } => '.',
+                    Cell::Shark { .. } => 'S',
+                },
+                &[],
+                |w| match w.populations().last() {
+                    Some((fish, sharks)) => format!("{} fish, {} sharks", fish, sharks),
+                    None => String::new(),
+                },
+                clock,
+            )?;
+            options.export(|| world.phase_csv())?;
+        }
+        WorldKind::Ising => {
+            use auto_worlds::ising::{Cell, WConfig, World, CRITICAL_TEMPERATURE};
+            let world_config = WConfig {
+                // the checkerboard only alternates across the edges with even dimensions
+                dimensions: Dimensions(dimensions.0 & !1, dimensions.1 & !1),
+                temperature: options.param("temperature", CRITICAL_TEMPERATURE)?,
+                coupling: options.param("coupling", 1.0)?,
+                field: options.param("field", 0.0)?,
+                dynamics: options.param("dynamics", Default::default())?,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+
+            cursive_canvas::run_with_tweaks(
+                world,
+                |c| match c {
+                    Cell::Up => '#',
+                    Cell::Down => ' ',
+                },
+                &[
+                    ('k', |w| w.set_temperature(w.temperature() + 0.1)),
+                    ('j', |w| w.set_temperature(w.temperature() - 0.1)),
+                ],
+                |w| {
+                    let last = w.measurements().last().copied().unwrap_or_default();
+                    format!(
+                        "T={:.2} (k/j), m={:.3}, E={:.3}",
+                        w.temperature(),
+                        last.magnetization,
+                        last.energy
+                    )
+                },
+                clock,
+            )?;
+        }
+        WorldKind::Schelling => {
+            use auto_worlds::{
+                neighborhood::Neighborhood,
+                schelling::{Cell, WConfig, World},
+            };
+            let world_config = WConfig {
+                dimensions,
+                groups: options.param("groups", 2)?,
+                vacancy: options.probability("vacancy", 0.1)?,
+                threshold: options.probability("threshold", 0.3)?,
+                neighborhood: Neighborhood::Moore,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+
+            cursive_canvas::run_with_tweaks(
+                world,
+                |c| match c {
+                    Cell::Vacant => ' ',
+                    Cell::Agent(group) => ['O', 'X', '+', '#'][group as usize % 4],
+                },
+                &[],
+                |w| {
+                    let last = w.segregation().last().copied().unwrap_or_default();
+                    format!(
+                        "similarity={:.3}, unhappy={:.3}",
+                        last.similarity, last.unhappy
+                    )
+                },
+                clock,
+            )?;
+        }
+        WorldKind::Voter => {
+            use auto_worlds::{
+                neighborhood::Neighborhood,
+                voter::{Cell, WConfig, World},
+            };
+            let world_config = WConfig {
+                dimensions,
+                rule: options.rule_or("voter").parse()?,
+                noise: options.probability("noise", 0.0)?,
+                neighborhood: Neighborhood::Moore,
+                seed: options.param("seed", rand::random())?,
+            };
+            let world = World::new_random(&mut rng, world_config);
+
+            cursive_canvas::run_with_tweaks(
+                world,
+                |c| match c {
+                    Cell::Yes => '#',
+                    Cell::No => ' ',
+                },
+                &[],
+                |w| {
+                    let last = w.consensus().last().copied().unwrap_or_default();
+                    format!(
+                        "majority={:.3}, interfaces={:.3}",
+                        last.majority, last.interfaces
+                    )
+                },
+                clock,
+            )?;
+        }
+        WorldKind::Turmite => {
+            use auto_worlds::turmite::{Direction, Rule, WConfig, World};
+            let world_config = WConfig {
+                dimensions,
+                rule: options.rule_or("langton").parse()?,
+                collision: options.param("collision", Default::default())?,
+            };
+            let world = World::random_with_ants(
+                &mut rng,
+                world_config,
+                options.param("ants", 1)?,
+                &options.list::<Rule>("rules")?,
+            );
+
+            cursive_canvas::run_with_overlay(
                 world,
                 |c| {
                     let colors = "!\"#$%&\'()*+,-./:;<=>?@[\\]^_`{|}~";
-                    match c {
-                        Cell::Ant(d, _) => match d {
-                            Direction::Left => '⇦',
-                            Direction::Right => '⇨',
-                            Direction::Up => '⇧',
-                            Direction::Down => '⇩',
-                        },
-                        Cell::Color(Color { value, .. }) => {
-                            colors.chars().nth(value).unwrap_or('?')
-                        }
-                    }
+                    colors.chars().nth(c.color as usize).unwrap_or('?')
+                },
+                |w| {
+                    w.ants()
+                        .iter()
+                        .map(|ant| {
+                            let arrow = match ant.direction {
+                                Direction::Left => '⇦',
+                                Direction::Right => '⇨',
+                                Direction::Up => '⇧',
+                                Direction::Down => '⇩',
+                            };
+                            (ant.position, arrow)
+                        })
+                        .collect()
                 },
-                update_millis,
+                clock,
             )?;
         }
     }
 
     Ok(())
 }
+
+/// A character for a level between 0 and 1, denser the higher the level
+fn shade(level: f64) -> char {
+    let shades = [' ', '.', ':', '+', '*'];
+    shades[(level.clamp(0.0, 1.0) * (shades.len() - 1) as f64).ceil() as usize]
+}
@@ -1,9 +1,15 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, time::Duration};
 
-use auto_cellular::{common::Dimensions, space::Space};
+use auto_cellular::{
+    clock::{Clock, ClockConfig},
+    common::Dimensions,
+    input::{Button, Input, Modifiers},
+    space::SpaceLike,
+    world::WorldLike,
+};
 use auto_spaces::wasm_canvas::{build_web, Browser};
 
-use auto_worlds::briansbrain::{Cell as BrainCell, World as BrainWorld};
+use auto_worlds::briansbrain::{Cell as BrainCell, WConfig as BrainConfig, World as BrainWorld};
 use wasm_bindgen::prelude::wasm_bindgen;
 
 #[wasm_bindgen]
@@ -24,15 +30,40 @@ pub fn get_config() -> Config {
     CONFIG
 }
 
-#[wasm_bindgen(js_name = "worldClick")]
-pub fn world_click(x: usize, y: usize) {
+/// `button` follows `MouseEvent.button`: 0 is primary, 1 is middle and 2 is secondary
+#[wasm_bindgen(js_name = "worldPress")]
+pub fn world_press(x: usize, y: usize, button: u8, shift: bool, ctrl: bool, alt: bool) {
+    let button = match button {
+        0 => Button::Primary,
+        1 => Button::Middle,
+        2 => Button::Secondary,
+        _ => return,
+    };
+    let modifiers = Modifiers { shift, ctrl, alt };
     BROWSER.with(|b| {
         let mut b = b.borrow_mut();
-        b.click_world((x, y));
+        INPUT.with(|i| {
+            i.borrow_mut()
+                .press(b.world_mut(), (x, y), button, modifiers)
+        });
         let _ = b.draw_delta();
     })
 }
 
+#[wasm_bindgen(js_name = "worldDrag")]
+pub fn world_drag(x: usize, y: usize) {
+    BROWSER.with(|b| {
+        let mut b = b.borrow_mut();
+        INPUT.with(|i| i.borrow_mut().drag(b.world_mut(), (x, y)));
+        let _ = b.draw_delta();
+    })
+}
+
+#[wasm_bindgen(js_name = "worldRelease")]
+pub fn world_release() {
+    INPUT.with(|i| i.borrow_mut().release())
+}
+
 #[wasm_bindgen(js_name = "blankWorld")]
 pub fn blank_world() {
     BROWSER.with(|b| {
@@ -45,18 +76,46 @@ pub fn blank_world() {
 pub fn world_reload() {
     BROWSER.with(|b| {
         let mut b = b.borrow_mut();
-        let _ = b.replace_with_random_world();
+        let config = b.world().config().clone();
+        *b.world_mut() = BrainWorld::new_random(&mut rand::thread_rng(), config);
+        let _ = b.draw_whole();
     })
 }
 
-#[wasm_bindgen(js_name = "tickBriansWorld")]
-pub fn tick_brians_world() {
+/// Runs one animation frame, `now` being the timestamp in milliseconds that
+/// `requestAnimationFrame` passes to its callback
+#[wasm_bindgen(js_name = "frameBriansWorld")]
+pub fn frame_brians_world(now: f64) {
     BROWSER.with(|b| {
         let mut b = b.borrow_mut();
-        let _ = b.tick_delta();
+        CLOCK.with(|c| {
+            let _ = c
+                .borrow_mut()
+                .drive(&mut *b, Duration::from_secs_f64(now.max(0.0) / 1000.0));
+        })
     })
 }
 
+#[wasm_bindgen(js_name = "togglePause")]
+pub fn toggle_pause() {
+    CLOCK.with(|c| c.borrow_mut().toggle_pause())
+}
+
+#[wasm_bindgen(js_name = "step")]
+pub fn step() {
+    CLOCK.with(|c| c.borrow_mut().step())
+}
+
+#[wasm_bindgen(js_name = "faster")]
+pub fn faster() {
+    CLOCK.with(|c| c.borrow_mut().faster())
+}
+
+#[wasm_bindgen(js_name = "slower")]
+pub fn slower() {
+    CLOCK.with(|c| c.borrow_mut().slower())
+}
+
 #[wasm_bindgen(js_name = "firstDrawBrains")]
 pub fn first_draw_brains() {
     BROWSER.with(|b| {
@@ -66,7 +125,9 @@ pub fn first_draw_brains() {
 }
 
 thread_local! {
-  static BROWSER: RefCell<Browser<BrainWorld>> = RefCell::new(build_web(Dimensions(CONFIG.width / CONFIG.pixel_size, CONFIG.height / CONFIG.pixel_size), |c| match c {
+  static INPUT: RefCell<Input<BrainCell>> = RefCell::new(Input::new());
+  static CLOCK: RefCell<Clock> = RefCell::new(Clock::new(ClockConfig::new(10.0, 60.0)));
+  static BROWSER: RefCell<Browser<BrainWorld>> = RefCell::new(build_web(BrainConfig { dimensions: Dimensions(CONFIG.width / CONFIG.pixel_size, CONFIG.height / CONFIG.pixel_size) }, |c| match c {
       BrainCell::On => "white",
       BrainCell::Dying => "blue",
       BrainCell::Off => "black"
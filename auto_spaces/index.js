@@ -4,3 +4,12 @@ export function setPixel(x, y, size, color) {
   ctx.fillStyle = color;
   ctx.fillRect(x * size, y * size, size, size);
 }
+
+export function setRun(x, y, size, colors) {
+  const world = document.getElementById("canvas");
+  const ctx = world.getContext("2d");
+  colors.forEach((color, i) => {
+    ctx.fillStyle = color;
+    ctx.fillRect((x + i) * size, y * size, size, size);
+  });
+}
@@ -1,4 +1,8 @@
+use auto_cellular::hex::Topology;
+
 pub struct OutputManager<Field> {
     pub field: Field,
     pub pixel_size: usize,
+    /// How the cells are laid out on the field
+    pub topology: Topology,
 }
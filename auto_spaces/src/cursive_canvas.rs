@@ -1,12 +1,24 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
 
 use auto_cellular::{
     cell::CellLike,
+    clock::{Clock, ClockConfig},
     common::{DoubleVec, Index},
-    space::{OutputField, SpaceLike},
+    hex::Topology,
+    input::{Button, Input, Modifiers},
+    space::{no_overlay, OutputField, Overlayer, SpaceLike},
     world::{WorldConfig, WorldLike},
 };
-use cursive::views::{LinearLayout, TextContent, TextView};
+use cursive::{
+    event::{Event, EventResult, EventTrigger, MouseButton, MouseEvent},
+    views::{LinearLayout, OnEventView, TextContent, TextView},
+};
 
 use crate::common::OutputManager;
 
@@ -17,7 +29,11 @@ where
     C: CellLike,
 {
     fn set_unit(&mut self, (x, y): Index, unit: char, _refresh: bool) -> Result<(), String> {
-        self.field[y][x].set_content(unit);
+        match self.topology {
+            Topology::Square => self.field[y][x].set_content(unit),
+            // hexagons take two characters so odd rows can be pushed half a cell over
+            Topology::Hex => self.field[y][x].set_content(format!("{} ", unit)),
+        }
         Ok(())
     }
     fn show(&mut self) {}
@@ -30,6 +46,7 @@ where
     world: W,
     output: Out,
     reprer: fn(W::Cell) -> char,
+    overlayer: Overlayer<W, char>,
 }
 
 impl<W> SpaceLike<W, Out> for Terminal<W>
@@ -53,25 +70,116 @@ where
     fn reprer(&self) -> Self::Reprer {
         self.reprer
     }
+
+    fn overlay(&self) -> Vec<(Index, char)> {
+        (self.overlayer)(&self.world)
+    }
 }
 
 impl<W> Terminal<W>
 where
     W: WorldLike,
 {
-    fn new(world: W, output: Out, reprer: <Self as SpaceLike<W, Out>>::Reprer) -> Self {
+    fn new(
+        world: W,
+        output: Out,
+        reprer: <Self as SpaceLike<W, Out>>::Reprer,
+        overlayer: Overlayer<W, char>,
+    ) -> Self {
         Self {
             world,
             output,
             reprer,
+            overlayer,
         }
     }
 }
 
-pub fn run<W>(world: W, repr: fn(W::Cell) -> char, update_millis: usize) -> Result<(), String>
+pub fn run<W>(world: W, repr: fn(W::Cell) -> char, clock: ClockConfig) -> Result<(), String>
 where
     W: WorldLike + Send + 'static,
 {
+    run_with_overlay(world, repr, no_overlay, clock)
+}
+
+/// Like [run], with `overlay` drawn over the cells, see [SpaceLike::overlay]
+pub fn run_with_overlay<W>(
+    world: W,
+    repr: fn(W::Cell) -> char,
+    overlay: Overlayer<W, char>,
+    clock: ClockConfig,
+) -> Result<(), String>
+where
+    W: WorldLike + Send + 'static,
+{
+    let extras = Extras {
+        overlay,
+        ..Extras::default()
+    };
+    launch(world, repr, extras, clock).map(drop)
+}
+
+/// A key and what it does to the world, such as changing one of its parameters
+pub type Tweak<W> = (char, fn(&mut W));
+
+/// Like [run], but the keys of `tweaks` change the world as it runs, and `status` tells
+/// how the world is doing under the cells. Keys already bound by [run] are left to it.
+/// The world is given back once the terminal is quit, e.g. to save what it measured
+pub fn run_with_tweaks<W>(
+    world: W,
+    repr: fn(W::Cell) -> char,
+    tweaks: &[Tweak<W>],
+    status: fn(&W) -> String,
+    clock: ClockConfig,
+) -> Result<W, String>
+where
+    W: WorldLike + Send + 'static,
+{
+    let extras = Extras {
+        tweaks,
+        status: Some(status),
+        ..Extras::default()
+    };
+    launch(world, repr, extras, clock)
+}
+
+/// What the variants of [run] add to it
+struct Extras<'a, W>
+where
+    W: WorldLike,
+{
+    tweaks: &'a [Tweak<W>],
+    status: Option<fn(&W) -> String>,
+    overlay: Overlayer<W, char>,
+}
+
+impl<'a, W> Default for Extras<'a, W>
+where
+    W: WorldLike,
+{
+    fn default() -> Self {
+        Self {
+            tweaks: &[],
+            status: None,
+            overlay: no_overlay,
+        }
+    }
+}
+
+fn launch<W>(
+    world: W,
+    repr: fn(W::Cell) -> char,
+    extras: Extras<W>,
+    clock: ClockConfig,
+) -> Result<W, String>
+where
+    W: WorldLike + Send + 'static,
+{
+    let Extras {
+        tweaks,
+        status,
+        overlay,
+    } = extras;
     let mut siv = cursive::default();
     siv.set_autorefresh(true);
 
@@ -90,29 +198,121 @@ where
         }
         layout_row
     });
+    let topology = world.config().topology();
     let mut layout = LinearLayout::vertical();
-    for textbox in textboxes {
+    for (y, mut textbox) in textboxes.enumerate() {
+        if topology == Topology::Hex && y % 2 == 1 {
+            textbox.insert_child(0, TextView::new(" "));
+        }
         layout.add_child(textbox);
     }
-    siv.add_layer(layout);
 
     let mut canvas = Terminal::new(
         world,
         OutputManager {
             field: texts,
             pixel_size: 1,
+            topology,
         },
         repr,
+        overlay,
     );
-    siv.add_global_callback('q', |s| s.quit());
     canvas.draw_whole()?;
+    let canvas = Arc::new(Mutex::new(canvas));
+
+    // every cell is a single character, or two for hexagons with the odd rows padded by
+    // one, so the mouse position maps straight to the index of the cell
+    let frame_interval = clock.frame_interval();
+    let clock = Arc::new(Mutex::new(Clock::new(clock)));
+    let input = Mutex::new(Input::new());
+    let mouse_canvas = Arc::clone(&canvas);
+    let mouse_clock = Arc::clone(&clock);
+    let grid = OnEventView::new(layout).on_pre_event_inner(EventTrigger::mouse(), move |_, e| {
+        if let Event::Mouse {
+            offset,
+            position,
+            event,
+        } = *e
+        {
+            let at = position.checked_sub(offset)?;
+            let at = match topology {
+                Topology::Square => (at.x, at.y),
+                Topology::Hex => (at.x.checked_sub(at.y % 2)? / 2, at.y),
+            };
+            let mut input = input.lock().unwrap();
+            let mut canvas = mouse_canvas.lock().unwrap();
+            if at.0 >= canvas.world().config().dimensions().0 {
+                return None;
+            }
+            match event {
+                MouseEvent::Press(b) => {
+                    // pressing pauses, so strokes land on a world that stands still
+                    mouse_clock.lock().unwrap().pause();
+                    let button = match b {
+                        MouseButton::Left => Button::Primary,
+                        MouseButton::Right => Button::Secondary,
+                        MouseButton::Middle => Button::Middle,
+                        _ => return None,
+                    };
+                    // cursive does not tell which modifiers are held with the mouse
+                    input.press(canvas.world_mut(), at, button, Modifiers::NONE)
+                }
+                MouseEvent::Hold(_) => input.drag(canvas.world_mut(), at),
+                MouseEvent::Release(_) => input.release(),
+                _ => return None,
+            }
+            let _ = canvas.draw_delta();
+        }
+        Some(EventResult::Consumed(None))
+    });
+    // the status goes under the grid, so it does not shift where the cells are
+    let status_text = TextContent::new("");
+    siv.add_layer(
+        LinearLayout::vertical()
+            .child(grid)
+            .child(TextView::new_with_content(status_text.clone())),
+    );
+    siv.add_global_callback('q', |s| s.quit());
+    for &(key, tweak) in tweaks {
+        let canvas = Arc::clone(&canvas);
+        siv.add_global_callback(key, move |_| tweak(canvas.lock().unwrap().world_mut()));
+    }
 
-    std::thread::spawn(move || loop {
-        let _ = canvas.tick_delta();
-        std::thread::sleep(Duration::from_millis(update_millis as u64));
+    for (key, action) in [
+        (' ', Clock::toggle_pause as fn(&mut Clock)),
+        ('n', Clock::step),
+        ('l', Clock::faster),
+        ('h', Clock::slower),
+    ] {
+        let clock = Arc::clone(&clock);
+        siv.add_global_callback(key, move |_| action(&mut clock.lock().unwrap()));
+    }
+
+    let ticking_canvas = Arc::clone(&canvas);
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = Arc::clone(&stop);
+    let ticker = std::thread::spawn(move || {
+        let start = Instant::now();
+        while !stopped.load(Ordering::Relaxed) {
+            let frame_start = Instant::now();
+            let mut canvas = ticking_canvas.lock().unwrap();
+            let _ = clock.lock().unwrap().drive(&mut *canvas, start.elapsed());
+            if let Some(status) = status {
+                status_text.set_content(status(canvas.world()));
+            }
+            drop(canvas);
+            std::thread::sleep(frame_interval.saturating_sub(frame_start.elapsed()));
+        }
     });
 
     siv.run();
+    stop.store(true, Ordering::Relaxed);
+    ticker
+        .join()
+        .map_err(|_| "the ticking thread panicked".to_string())?;
 
-    Ok(())
+    // the callbacks of the terminal still hold the canvas, so it is left a blank world
+    let mut canvas = canvas.lock().unwrap();
+    let blank = canvas.world().blank();
+    Ok(std::mem::replace(canvas.world_mut(), blank))
 }
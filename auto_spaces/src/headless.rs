@@ -0,0 +1,306 @@
+use auto_cellular::{
+    cell::CellLike,
+    common::{Dimensions, DoubleVec, Index},
+    hex::Topology,
+    space::{no_overlay, OutputField, Overlayer, SpaceLike},
+    world::{WorldConfig, WorldLike},
+};
+
+use crate::common::OutputManager;
+
+/// A unit of an RGBA image
+pub type Rgba = [u8; 4];
+
+/// An in-memory grid of units, for tests and offscreen rendering
+pub struct Framebuffer<S> {
+    units: DoubleVec<S>,
+    frames: usize,
+}
+
+impl<S: Clone> Framebuffer<S> {
+    pub fn new(Dimensions(width, height): Dimensions, blank: S) -> Self {
+        Self {
+            units: vec![vec![blank; width]; height],
+            frames: 0,
+        }
+    }
+
+    pub fn dimensions(&self) -> Dimensions {
+        Dimensions(
+            self.units.first().map_or(0, |row| row.len()),
+            self.units.len(),
+        )
+    }
+
+    /// All the units, one row at a time
+    pub fn units(&self) -> &DoubleVec<S> {
+        &self.units
+    }
+
+    pub fn unit(&self, (x, y): Index) -> Option<&S> {
+        self.units.get(y).and_then(|row| row.get(x))
+    }
+
+    /// How many times the buffer was shown
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+}
+
+impl Framebuffer<char> {
+    /// The buffer as lines of text
+    pub fn to_text(&self) -> String {
+        self.units
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Framebuffer<Rgba> {
+    /// The buffer as tightly packed, row-major RGBA bytes
+    pub fn to_rgba_bytes(&self) -> Vec<u8> {
+        self.units.iter().flatten().flatten().copied().collect()
+    }
+}
+
+pub type Offscreen<S> = OutputManager<Framebuffer<S>>;
+
+impl<C, S> OutputField<C, S> for Offscreen<S>
+where
+    C: CellLike,
+    S: Clone,
+{
+    fn set_unit(&mut self, (x, y): Index, unit: S, refresh: bool) -> Result<(), String> {
+        let size = self.pixel_size;
+        // odd rows of hexagons are pushed half a cell to the right
+        let shift = match self.topology {
+            Topology::Hex if y % 2 == 1 => size / 2,
+            _ => 0,
+        };
+        let left = x * size + shift;
+        let outside = || format!("({}, {}) is outside the framebuffer", x, y);
+        let rows = self
+            .field
+            .units
+            .get_mut(y * size..(y + 1) * size)
+            .ok_or_else(outside)?;
+        for row in rows {
+            row.get_mut(left..left + size)
+                .ok_or_else(outside)?
+                .fill(unit.clone());
+        }
+
+        if refresh {
+            OutputField::<C, S>::show(self)
+        }
+
+        Ok(())
+    }
+
+    fn show(&mut self) {
+        self.field.frames += 1;
+    }
+}
+
+/// A [SpaceLike] that renders into a [Framebuffer] instead of a screen
+pub struct Headless<W, S>
+where
+    W: WorldLike,
+{
+    world: W,
+    output: Offscreen<S>,
+    reprer: fn(W::Cell) -> S,
+    overlayer: Overlayer<W, S>,
+}
+
+impl<W, S> SpaceLike<W, Offscreen<S>> for Headless<W, S>
+where
+    W: WorldLike,
+    S: Clone,
+{
+    type CellRepr = S;
+    type Reprer = fn(W::Cell) -> S;
+
+    fn world_mut(&mut self) -> &mut W {
+        &mut self.world
+    }
+
+    fn world(&self) -> &W {
+        &self.world
+    }
+
+    fn output_mut(&mut self) -> &mut Offscreen<S> {
+        &mut self.output
+    }
+
+    fn reprer(&self) -> Self::Reprer {
+        self.reprer
+    }
+
+    fn overlay(&self) -> Vec<(Index, S)> {
+        (self.overlayer)(&self.world)
+    }
+}
+
+impl<W, S> Headless<W, S>
+where
+    W: WorldLike,
+    S: Clone,
+{
+    /// Every cell of the world takes `pixel_size` x `pixel_size` units of the framebuffer,
+    /// which starts out filled with `blank`. In a [Topology::Hex] world, odd rows are
+    /// pushed `pixel_size / 2` units to the right, so the framebuffer is that much wider
+    /// and hexagons only look staggered from a `pixel_size` of 2
+    pub fn new(world: W, pixel_size: usize, blank: S, reprer: fn(W::Cell) -> S) -> Self {
+        let Dimensions(width, height) = *world.config().dimensions();
+        let topology = world.config().topology();
+        let margin = match topology {
+            Topology::Square => 0,
+            Topology::Hex => pixel_size / 2,
+        };
+        Self {
+            world,
+            output: OutputManager {
+                field: Framebuffer::new(
+                    Dimensions(width * pixel_size + margin, height * pixel_size),
+                    blank,
+                ),
+                pixel_size,
+                topology,
+            },
+            reprer,
+            overlayer: no_overlay,
+        }
+    }
+
+    /// Draws `overlayer`'s units over the cells, see [SpaceLike::overlay]
+    pub fn with_overlay(self, overlayer: Overlayer<W, S>) -> Self {
+        Self { overlayer, ..self }
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer<S> {
+        &self.output.field
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use auto_worlds::gameoflife::{Cell, WConfig, World};
+
+    fn blinker() -> World {
+        let mut cells = vec![vec![Cell::Dead; 5]; 5];
+        cells[2][1..=3].fill(Cell::Alive);
+        World::new(
+            cells,
+            WConfig {
+                dimensions: Dimensions(5, 5),
+            },
+        )
+    }
+
+    fn text(c: Cell) -> char {
+        match c {
+            Cell::Alive => '#',
+            Cell::Dead => '.',
+        }
+    }
+
+    #[test]
+    fn draw_whole_renders_every_cell() {
+        let mut space = Headless::new(blinker(), 1, ' ', text);
+        space.draw_whole().unwrap();
+
+        assert_eq!(
+            space.framebuffer().to_text(),
+            ".....\n.....\n.###.\n.....\n....."
+        );
+        assert_eq!(space.framebuffer().frames(), 1);
+    }
+
+    #[test]
+    fn tick_delta_only_redraws_changes() {
+        let mut space = Headless::new(blinker(), 1, ' ', text);
+        space.draw_whole().unwrap();
+        space.tick_delta().unwrap();
+
+        assert_eq!(
+            space.framebuffer().to_text(),
+            ".....\n..#..\n..#..\n..#..\n....."
+        );
+
+        // cells that did not change are never touched by the delta
+        let mut space = Headless::new(blinker(), 1, ' ', text);
+        space.tick_delta().unwrap();
+        assert_eq!(
+            space.framebuffer().to_text(),
+            "     \n  #  \n . . \n  #  \n     "
+        );
+    }
+
+    #[test]
+    fn overlays_follow_the_ants() {
+        use auto_worlds::turmite::{self, Collision, Rule};
+
+        let config = turmite::WConfig {
+            dimensions: Dimensions(3, 3),
+            rule: Rule::default(),
+            collision: Collision::Stack,
+        };
+        let world = turmite::World::new_blank(config);
+        let mut space = Headless::new(world, 1, ' ', |c: turmite::Cell| {
+            ['.', '#'][c.color as usize]
+        })
+        .with_overlay(|w| w.ants().iter().map(|ant| (ant.position, 'A')).collect());
+        space.draw_whole().unwrap();
+        assert_eq!(space.framebuffer().to_text(), "...\n.A.\n...");
+
+        // the cell the ant left is drawn again under it
+        space.tick_delta().unwrap();
+        assert_eq!(space.framebuffer().to_text(), "...\n.#A\n...");
+    }
+
+    #[test]
+    fn odd_hexagon_rows_are_staggered() {
+        use auto_worlds::hexlife::{self, Rule, Seed};
+
+        let config = hexlife::WConfig {
+            dimensions: Dimensions(3, 2),
+            rule: Rule::default(),
+            seed: Seed::Random,
+        };
+        let mut cells = vec![vec![Cell::Dead; 3]; 2];
+        cells[0][0] = Cell::Alive;
+        cells[1][0] = Cell::Alive;
+        let mut space = Headless::new(hexlife::World::new(cells, config), 2, ' ', text);
+        space.draw_whole().unwrap();
+        assert_eq!(
+            space.framebuffer().to_text(),
+            "##.... \n##.... \n ##....\n ##...."
+        );
+    }
+
+    #[test]
+    fn pixel_size_scales_rgba_output() {
+        let mut space = Headless::new(blinker(), 2, [0; 4], |c| match c {
+            Cell::Alive => [255; 4],
+            Cell::Dead => [0, 0, 0, 255],
+        });
+        space.draw_whole().unwrap();
+
+        let framebuffer = space.framebuffer();
+        assert_eq!(framebuffer.dimensions().0, 10);
+        assert_eq!(framebuffer.unit((2, 4)), Some(&[255; 4]));
+        assert_eq!(framebuffer.unit((1, 4)), Some(&[0, 0, 0, 255]));
+        assert_eq!(framebuffer.to_rgba_bytes().len(), 10 * 10 * 4);
+
+        // units past any edge are an error rather than dropped
+        let output = &mut space.output;
+        for at in [(5, 0), (0, 5)] {
+            let result = OutputField::<Cell, Rgba>::set_unit(output, at, [9; 4], false);
+            assert_eq!(result, Err(format!("{:?} is outside the framebuffer", at)));
+        }
+    }
+}
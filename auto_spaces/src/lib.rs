@@ -3,6 +3,7 @@ use std::{fmt::Display, str::FromStr};
 pub mod common;
 #[cfg(feature = "cursive")]
 pub mod cursive_canvas;
+pub mod headless;
 #[cfg(feature = "sdl2")]
 pub mod sdl2_canvas;
 
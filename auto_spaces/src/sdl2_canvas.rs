@@ -2,12 +2,21 @@ use std::time::{Duration, Instant};
 
 use auto_cellular::{
     cell::CellLike,
+    clock::{Clock, ClockConfig},
     common::{Dimensions, Index},
-    space::{OutputField, SpaceLike},
-    world::WorldLike,
+    hex::{Layout, Topology},
+    input::{Action, Button, Input, Modifiers},
+    space::{no_overlay, OutputField, Overlayer, SpaceLike},
+    world::{WorldConfig, WorldLike},
 };
 use sdl2::{
-    event::Event, keyboard::Keycode, pixels::Color, rect::Rect, render::Canvas, video::Window,
+    event::Event,
+    keyboard::{Keycode, Mod},
+    mouse::MouseButton,
+    pixels::Color,
+    rect::Rect,
+    render::Canvas,
+    video::Window,
 };
 
 use crate::common::OutputManager;
@@ -15,29 +24,80 @@ use crate::common::OutputManager;
 pub struct Config {
     pub dimensions: Dimensions, // dimensions of window
     pub pixel_size: usize,
-    pub millis: u64,
+    pub clock: ClockConfig,
 }
 
 impl Config {
-    pub fn new(dimensions: Dimensions, pixel_size: usize, millis: u64) -> Self {
+    pub fn new(dimensions: Dimensions, pixel_size: usize, clock: ClockConfig) -> Self {
         Self {
             dimensions,
             pixel_size,
-            millis,
+            clock,
         }
     }
 
     pub fn pixel_count_x(&self) -> usize {
-        self.dimensions.0 as usize / self.pixel_size
+        self.dimensions.0 / self.pixel_size
     }
 
     pub fn pixel_count_y(&self) -> usize {
-        self.dimensions.1 as usize / self.pixel_size
+        self.dimensions.1 / self.pixel_size
     }
 
     pub fn downscale(&self, (x, y): (isize, isize)) -> Index {
         (x as usize / self.pixel_size, y as usize / self.pixel_size)
     }
+
+    /// The cell of a world laid out in `topology` under the point `(x, y)` of the window
+    pub fn pick(
+        &self,
+        topology: Topology,
+        dimensions: Dimensions,
+        (x, y): (i32, i32),
+    ) -> Option<Index> {
+        match topology {
+            Topology::Square => {
+                let (i, j) = self.downscale((x.max(0) as isize, y.max(0) as isize));
+                (i < dimensions.0 && j < dimensions.1).then_some((i, j))
+            }
+            Topology::Hex => {
+                Layout::new(self.pixel_size as f64).pick((x as f64, y as f64), dimensions)
+            }
+        }
+    }
+}
+
+fn button(button: MouseButton) -> Option<Button> {
+    match button {
+        MouseButton::Left => Some(Button::Primary),
+        MouseButton::Right => Some(Button::Secondary),
+        MouseButton::Middle => Some(Button::Middle),
+        _ => None,
+    }
+}
+
+/// Which of the number keys 1 to 9 was pressed, counting from 0
+fn slot(key: Keycode) -> Option<usize> {
+    let keys = [
+        Keycode::Num1,
+        Keycode::Num2,
+        Keycode::Num3,
+        Keycode::Num4,
+        Keycode::Num5,
+        Keycode::Num6,
+        Keycode::Num7,
+        Keycode::Num8,
+        Keycode::Num9,
+    ];
+    keys.iter().position(|&k| k == key)
+}
+
+fn modifiers(state: Mod) -> Modifiers {
+    Modifiers {
+        shift: state.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        ctrl: state.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+        alt: state.intersects(Mod::LALTMOD | Mod::RALTMOD),
+    }
 }
 
 type Out<'a> = OutputManager<&'a mut Canvas<Window>>;
@@ -47,15 +107,27 @@ where
     C: CellLike,
 {
     fn set_unit(&mut self, (x, y): Index, unit: Color, refresh: bool) -> Result<(), String> {
-        let rect = Rect::new(
-            (x * self.pixel_size) as i32,
-            (y * self.pixel_size) as i32,
-            self.pixel_size as u32,
-            self.pixel_size as u32,
-        );
-
         self.field.set_draw_color(unit);
-        self.field.fill_rect(rect)?;
+        match self.topology {
+            Topology::Square => {
+                let rect = Rect::new(
+                    (x * self.pixel_size) as i32,
+                    (y * self.pixel_size) as i32,
+                    self.pixel_size as u32,
+                    self.pixel_size as u32,
+                );
+                self.field.fill_rect(rect)?;
+            }
+            // filled hexagons, one line of pixels at a time
+            Topology::Hex => {
+                let rects: Vec<Rect> = Layout::new(self.pixel_size as f64)
+                    .spans((x, y))
+                    .into_iter()
+                    .map(|(x, y, length)| Rect::new(x as i32, y as i32, length as u32, 1))
+                    .collect();
+                self.field.fill_rects(&rects)?;
+            }
+        }
 
         if refresh {
             OutputField::<C, Color>::show(self)
@@ -64,6 +136,37 @@ where
         Ok(())
     }
 
+    /// Neighbouring units of the same color are filled with a single rect
+    fn set_run(&mut self, (x, y): Index, units: Vec<Color>) -> Result<(), String> {
+        if self.topology == Topology::Hex {
+            for (i, unit) in units.into_iter().enumerate() {
+                OutputField::<C, Color>::set_unit(self, (x + i, y), unit, false)?
+            }
+            return Ok(());
+        }
+
+        let mut start = 0;
+        while start < units.len() {
+            let color = units[start];
+            let len = units[start..]
+                .iter()
+                .take_while(|&&unit| unit == color)
+                .count();
+            let rect = Rect::new(
+                ((x + start) * self.pixel_size) as i32,
+                (y * self.pixel_size) as i32,
+                (len * self.pixel_size) as u32,
+                self.pixel_size as u32,
+            );
+
+            self.field.set_draw_color(color);
+            self.field.fill_rect(rect)?;
+            start += len;
+        }
+
+        Ok(())
+    }
+
     fn show(&mut self) {
         self.field.present()
     }
@@ -76,6 +179,7 @@ where
     world: W,
     output: Out<'a>,
     reprer: fn(W::Cell) -> Color,
+    overlayer: Overlayer<W, Color>,
 }
 
 impl<'a, W> SpaceLike<W, Out<'a>> for Gui<'a, W>
@@ -99,17 +203,27 @@ where
     fn reprer(&self) -> Self::Reprer {
         self.reprer
     }
+
+    fn overlay(&self) -> Vec<(Index, Color)> {
+        (self.overlayer)(&self.world)
+    }
 }
 
 impl<'a, W> Gui<'a, W>
 where
     W: WorldLike,
 {
-    fn new(world: W, output: Out<'a>, reprer: fn(W::Cell) -> Color) -> Self {
+    fn new(
+        world: W,
+        output: Out<'a>,
+        reprer: fn(W::Cell) -> Color,
+        overlayer: Overlayer<W, Color>,
+    ) -> Self {
         Gui {
             world,
             output,
             reprer,
+            overlayer,
         }
     }
 
@@ -129,8 +243,113 @@ pub fn run<W>(
 where
     W: WorldLike,
 {
-    let mut millis = config.millis;
+    run_with_palette(config, world, title, repr, &[])
+}
+
+/// Like [run], but the primary button paints with a state picked from `palette` with the
+/// number keys (1 for the first one) instead of cycling cells. An empty palette keeps
+/// the default bindings of [Input]
+pub fn run_with_palette<W>(
+    config: Config,
+    world: W,
+    title: &str,
+    repr: fn(W::Cell) -> Color,
+    palette: &[(&str, W::Cell)],
+) -> Result<(), String>
+where
+    W: WorldLike,
+{
+    let extras = Extras {
+        palette,
+        ..Extras::default()
+    };
+    launch(config, world, title, repr, extras).map(drop)
+}
+
+/// Like [run], with `overlay` drawn over the cells, see [SpaceLike::overlay]
+pub fn run_with_overlay<W>(
+    config: Config,
+    world: W,
+    title: &str,
+    repr: fn(W::Cell) -> Color,
+    overlay: Overlayer<W, Color>,
+) -> Result<(), String>
+where
+    W: WorldLike,
+{
+    let extras = Extras {
+        overlay,
+        ..Extras::default()
+    };
+    launch(config, world, title, repr, extras).map(drop)
+}
+
+/// A key and what it does to the world, such as changing one of its parameters
+pub type Tweak<W> = (Keycode, fn(&mut W));
+
+/// Like [run], but the keys of `tweaks` change the world as it runs, and `status` tells
+/// how the world is doing in the title. Keys already bound by [run] are left to it. The
+/// world is given back once the window is closed, e.g. to save what it measured
+pub fn run_with_tweaks<W>(
+    config: Config,
+    world: W,
+    title: &str,
+    repr: fn(W::Cell) -> Color,
+    tweaks: &[Tweak<W>],
+    status: fn(&W) -> String,
+) -> Result<W, String>
+where
+    W: WorldLike,
+{
+    let extras = Extras {
+        tweaks,
+        status: Some(status),
+        ..Extras::default()
+    };
+    launch(config, world, title, repr, extras)
+}
+
+/// What the variants of [run] add to it
+struct Extras<'a, W>
+where
+    W: WorldLike,
+{
+    palette: &'a [(&'a str, W::Cell)],
+    tweaks: &'a [Tweak<W>],
+    status: Option<fn(&W) -> String>,
+    overlay: Overlayer<W, Color>,
+}
 
+impl<'a, W> Default for Extras<'a, W>
+where
+    W: WorldLike,
+{
+    fn default() -> Self {
+        Self {
+            palette: &[],
+            tweaks: &[],
+            status: None,
+            overlay: no_overlay,
+        }
+    }
+}
+
+fn launch<W>(
+    config: Config,
+    world: W,
+    title: &str,
+    repr: fn(W::Cell) -> Color,
+    extras: Extras<W>,
+) -> Result<W, String>
+where
+    W: WorldLike,
+{
+    let Extras {
+        palette,
+        tweaks,
+        status,
+        overlay,
+    } = extras;
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video()?;
 
@@ -146,21 +365,31 @@ where
         .map_err(|s| s.to_string())?;
 
     let mut canvas = window.into_canvas().build().map_err(|s| s.to_string())?;
+    let topology = world.config().topology();
+    let dimensions = *world.config().dimensions();
     let output = OutputManager {
         field: &mut canvas,
         pixel_size: config.pixel_size,
+        topology,
     };
 
-    let mut gui = Gui::new(world, output, repr);
+    let mut gui = Gui::new(world, output, repr, overlay);
     gui.clear_output();
     gui.draw_whole()?;
 
     let mut event_dump = sdl_context.event_pump()?;
 
-    let mut is_paused = true;
-    let mut i = 0;
-    let mut m: Duration = Duration::from_nanos(0);
+    let mut input = Input::new();
+    if let Some(&(_, first)) = palette.first() {
+        input.bind(Button::Primary, Modifiers::NONE, Action::Paint);
+        input.set_primary(first);
+    }
+    let mut clock = Clock::new(config.clock);
+    clock.pause();
+    let start = Instant::now();
+    let mut last_report = start;
     'running: loop {
+        let frame_start = Instant::now();
         for event in event_dump.poll_iter() {
             match event {
                 Event::Quit { .. }
@@ -179,41 +408,51 @@ where
                 Event::KeyDown {
                     keycode: Some(Keycode::Space),
                     ..
-                } => {
-                    is_paused = !is_paused;
-                }
+                } => clock.toggle_pause(),
                 Event::KeyDown {
                     keycode: Some(Keycode::L),
                     ..
-                } => {
-                    millis = 10.max(millis - 10);
-                }
+                } => clock.faster(),
                 Event::KeyDown {
                     keycode: Some(Keycode::H),
                     ..
-                } => {
-                    millis += 10;
-                }
+                } => clock.slower(),
                 Event::KeyDown {
                     keycode: Some(Keycode::N),
                     ..
-                } => {
-                    if is_paused {
-                        gui.tick_whole()?
-                    }
-                }
+                } => clock.step(),
 
                 Event::KeyDown {
                     keycode: Some(Keycode::B),
                     ..
                 } => gui.replace_with_blank_world()?,
-                Event::MouseButtonDown { x, y, .. } => {
-                    let (dx, dy) = config.downscale((x as isize, y as isize));
-                    if is_paused {
-                        gui.click_world((dx, dy));
-                        gui.draw_whole()?
-                    } else {
-                        is_paused = true;
+                Event::MouseButtonDown {
+                    mouse_btn, x, y, ..
+                } => {
+                    // pressing pauses, so strokes land on a world that stands still
+                    clock.pause();
+                    let at = config.pick(topology, dimensions, (x, y));
+                    if let (Some(button), Some(at)) = (button(mouse_btn), at) {
+                        let modifiers = modifiers(sdl_context.keyboard().mod_state());
+                        input.press(gui.world_mut(), at, button, modifiers);
+                        gui.draw_delta()?
+                    }
+                }
+                Event::MouseMotion { x, y, .. } if input.is_stroking() => {
+                    if let Some(to) = config.pick(topology, dimensions, (x, y)) {
+                        input.drag(gui.world_mut(), to);
+                        gui.draw_delta()?
+                    }
+                }
+                Event::MouseButtonUp { .. } => input.release(),
+                Event::KeyDown {
+                    keycode: Some(key), ..
+                } => {
+                    if let Some(&(_, cell)) = slot(key).and_then(|i| palette.get(i)) {
+                        input.set_primary(cell);
+                    }
+                    if let Some((_, tweak)) = tweaks.iter().find(|(k, _)| *k == key) {
+                        tweak(gui.world_mut());
                     }
                 }
 
@@ -221,22 +460,33 @@ where
             }
         }
 
-        if !is_paused {
-            let now = Instant::now();
-            gui.tick_whole()?;
-            let d = now.elapsed();
-            m = m.max(d);
-            if i % 61 == 0 {
-                println!("{:?}", now.elapsed());
+        clock.drive(&mut gui, start.elapsed())?;
+
+        if last_report.elapsed() >= Duration::from_secs(1) {
+            last_report = Instant::now();
+            let mut title = format!(
+                "{} - {:.0} tps ({:.0} target), {:.0} fps",
+                title,
+                clock.tps(),
+                clock.target_tps(),
+                clock.fps()
+            );
+            if let Some((name, _)) = palette.iter().find(|(_, c)| *c == input.primary()) {
+                title = format!("{} - painting {}", title, name);
             }
-
-            i += 1;
+            if let Some(status) = status {
+                title = format!("{} - {}", title, status(gui.world()));
+            }
+            let _ = gui.output_mut().field.window_mut().set_title(&title);
         }
 
-        std::thread::sleep(Duration::from_millis(millis));
+        std::thread::sleep(
+            config
+                .clock
+                .frame_interval()
+                .saturating_sub(frame_start.elapsed()),
+        );
     }
 
-    println!("{:?}", m);
-
-    Ok(())
+    Ok(gui.world)
 }
@@ -1,8 +1,9 @@
 pub(crate) use auto_cellular::{
-    cell::BasicCell,
-    common::{Dimensions, Index},
-    space::{OutputField, Space},
-    world::BasicWorld,
+    cell::CellLike,
+    common::Index,
+    hex::Topology,
+    space::{OutputField, SpaceLike},
+    world::WorldLike,
 };
 use wasm_bindgen::prelude::*;
 
@@ -12,6 +13,9 @@ use crate::common::OutputManager;
 extern "C" {
     #[wasm_bindgen(js_name = "setPixel")]
     pub fn set_pixel(x: usize, y: usize, pixel_size: usize, new_class: Color);
+
+    #[wasm_bindgen(js_name = "setRun")]
+    pub fn set_run(x: usize, y: usize, pixel_size: usize, colors: Vec<JsValue>);
 }
 
 pub type Color = String;
@@ -20,28 +24,39 @@ pub type Colors = OutputManager<()>;
 
 impl<C> OutputField<C, Color> for Colors
 where
-    C: BasicCell,
+    C: CellLike,
 {
     fn set_unit(&mut self, (x, y): Index, unit: Color, _refresh: bool) -> Result<(), String> {
         set_pixel(x, y, self.pixel_size, unit); // let's see
         Ok(())
     }
 
+    /// A whole run crosses the wasm boundary in one call
+    fn set_run(&mut self, (x, y): Index, units: Vec<Color>) -> Result<(), String> {
+        set_run(
+            x,
+            y,
+            self.pixel_size,
+            units.into_iter().map(JsValue::from).collect(),
+        );
+        Ok(())
+    }
+
     fn show(&mut self) {}
 }
 
 pub struct Browser<W>
 where
-    W: BasicWorld,
+    W: WorldLike,
 {
     world: W,
     output: Colors,
     reprer: fn(W::Cell) -> Color,
 }
 
-impl<W> Space<W, Colors> for Browser<W>
+impl<W> SpaceLike<W, Colors> for Browser<W>
 where
-    W: BasicWorld,
+    W: WorldLike,
 {
     type CellRepr = Color;
     type Reprer = fn(W::Cell) -> Color;
@@ -64,7 +79,7 @@ where
 
 impl<W> Browser<W>
 where
-    W: BasicWorld,
+    W: WorldLike,
 {
     pub fn new(world: W, output: Colors, reprer: fn(W::Cell) -> Color) -> Self {
         Self {
@@ -75,21 +90,18 @@ where
     }
 }
 
-pub fn build_web<W>(
-    dimensions: Dimensions,
-    repr: fn(W::Cell) -> Color,
-    pixel_size: usize,
-) -> Browser<W>
+pub fn build_web<W>(config: W::Config, repr: fn(W::Cell) -> Color, pixel_size: usize) -> Browser<W>
 where
-    W: BasicWorld,
+    W: WorldLike,
 {
     let mut rng = rand::thread_rng();
-    let world = W::new_random(&mut rng, dimensions);
+    let world = W::new_random(&mut rng, config);
     Browser::new(
         world,
         OutputManager {
             field: (),
             pixel_size,
+            topology: Topology::Square,
         },
         repr,
     )
@@ -14,6 +14,12 @@ pub enum Cell {
     Off,
 }
 
+auto_cellular::finite_cell!(Cell {
+    Off => "Off",
+    On => "On",
+    Dying => "Dying",
+});
+
 impl CellLike for Cell {
     fn next_state(&self) -> Self {
         match *self {
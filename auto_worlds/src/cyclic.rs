@@ -0,0 +1,269 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+use crate::neighborhood::count_around;
+pub use crate::{generations::Cell, neighborhood::Neighborhood};
+
+/// Well known cyclic rules that can be asked for by name
+pub const PRESETS: [(&str, &str); 8] = [
+    ("313", "R1/T3/C3/NM"),
+    ("amoeba", "R3/T10/C2/NN"),
+    ("cca", "R1/T1/C14/NN"),
+    ("cyclicspirals", "R3/T5/C8/NM"),
+    ("perfectspirals", "R1/T3/C4/NM"),
+    ("squarishspirals", "R2/T2/C6/NN"),
+    ("stripes", "R3/T4/C5/NN"),
+    ("turbulent", "R2/T5/C8/NM"),
+];
+
+/// Range, threshold, number of states and neighborhood, written like `R1/T3/C3/NM`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub range: usize,
+    pub threshold: usize,
+    pub states: u8,
+    pub neighborhood: Neighborhood,
+}
+
+impl Rule {
+    /// Parses a rulestring, or looks `s` up in `presets`
+    pub fn parse_with(s: &str, presets: &[(&str, &str)]) -> Result<Self, String> {
+        let s = s.trim();
+        if let Some((_, rule)) = presets
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return rule.parse();
+        }
+
+        let (mut range, mut threshold, mut states) = (None, None, None);
+        let mut neighborhood = Neighborhood::Moore;
+        for part in s.split(['/', ',']) {
+            let part = part.trim().to_ascii_uppercase();
+            let mut chars = part.chars();
+            let key = chars.next().ok_or("empty part in rulestring")?;
+            let value = chars.as_str();
+            let bad = || format!("`{}` is not a valid part", part);
+            match key {
+                'R' => range = Some(value.parse::<usize>().map_err(|_| bad())?),
+                'T' => threshold = Some(value.parse::<usize>().map_err(|_| bad())?),
+                'C' => states = Some(value.parse::<u8>().map_err(|_| bad())?),
+                'N' => {
+                    neighborhood = match value {
+                        "M" => Neighborhood::Moore,
+                        "N" => Neighborhood::VonNeumann,
+                        _ => return Err(bad()),
+                    }
+                }
+                _ => return Err(bad()),
+            }
+        }
+
+        let missing = |what: &str| format!("`{}` is missing {}", s, what);
+        let rule = Self {
+            range: range.ok_or_else(|| missing("the range `R`"))?,
+            threshold: threshold.ok_or_else(|| missing("the threshold `T`"))?,
+            states: states.ok_or_else(|| missing("the states `C`"))?,
+            neighborhood,
+        };
+        if rule.range == 0 {
+            return Err(String::from("the range has to be at least 1"));
+        }
+        if rule.states < 2 {
+            return Err(String::from("a cyclic rule needs at least 2 states"));
+        }
+        Ok(rule)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        PRESETS[0].1.parse().unwrap()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses rulestrings like `R1/T3/C3/NM` (commas work as well as slashes) and the
+    /// names in [PRESETS]. The neighborhood may be left out, in which case it is Moore
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, &PRESETS)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "R{}/T{}/C{}/N{}",
+            self.range,
+            self.threshold,
+            self.states,
+            match self.neighborhood {
+                Neighborhood::Moore => 'M',
+                Neighborhood::VonNeumann => 'N',
+            }
+        )
+    }
+}
+
+/// A grid of uniformly random states out of `states`
+pub(crate) fn soup<R: rand::Rng + ?Sized>(
+    rng: &mut R,
+    Dimensions(width, height): Dimensions,
+    states: u8,
+) -> DoubleVec<Cell> {
+    (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| Cell::new(rng.gen_range(0..states), states))
+                .collect()
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// Every cell moves on to the next state (wrapping around to 0) once at least
+/// `threshold` of its neighbors are already there
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    offsets: Vec<(isize, isize)>,
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    /// Cells are brought to the number of states of the rule
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let states = config.rule.states;
+        let cells: DoubleVec<Cell> = cells
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|c| Cell::new(c.state(), states))
+                    .collect()
+            })
+            .collect();
+        let clone = cells.clone();
+        Self {
+            cells,
+            offsets: config.rule.neighborhood.offsets(config.rule.range),
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    /// Every state is equally likely
+    fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        let cells = soup(rng, config.dimensions, config.rule.states);
+        Self::new(cells, config)
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+        let rule = &self.config().rule;
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                let next = Cell::new(self.cells[j][i].state() + 1, rule.states);
+                let count = count_around(&self.cells, &self.offsets, p, |c| c == next);
+                if count >= rule.threshold {
+                    delta.push((p, next));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    /// Painted cells are brought to the number of states of the rule
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        let states = self.config.rule.states;
+        let changes: Vec<(Index, Cell)> = changes
+            .into_iter()
+            .map(|(i, c)| (i, Cell::new(c.state(), states)))
+            .collect();
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_rulestrings() {
+        let rule: Rule = "R1/T3/C3/NM".parse().unwrap();
+        assert_eq!(rule, Rule::default());
+        assert_eq!("r1,t3,c3".parse::<Rule>(), Ok(rule));
+        assert_eq!("cca".parse::<Rule>().unwrap().to_string(), "R1/T1/C14/NN");
+        assert!("R1/T3".parse::<Rule>().is_err());
+        assert!("R0/T3/C3".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn cells_advance_past_the_threshold() {
+        let config = WConfig {
+            dimensions: Dimensions(5, 5),
+            rule: "R1/T2/C3/NN".parse().unwrap(),
+        };
+        let mut cells = vec![vec![Cell::new(0, 3); 5]; 5];
+        cells[1][2] = Cell::new(1, 3);
+        cells[2][1] = Cell::new(1, 3);
+        cells[3][3] = Cell::new(2, 3);
+        let mut world = World::new(cells, config);
+        world.tick();
+
+        let cells = world.cells();
+        // two von Neumann neighbors in state 1 push the center on
+        assert_eq!(cells[2][2].state(), 1);
+        // only one of them is next to (3, 1)
+        assert_eq!(cells[1][3].state(), 0);
+        // states wrap around, so a 2 surrounded by 0s becomes a 0
+        assert_eq!(cells[3][3].state(), 0);
+        // and nothing around the 1s is in state 2 yet
+        assert_eq!(cells[1][2].state(), 1);
+    }
+}
@@ -0,0 +1,331 @@
+use auto_cellular::{
+    cell::{CellLike, FiniteCellLike},
+    common::{diff, linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Chance that a material sinking through a liquid (rather than air) moves on a tick
+pub const VISCOSITY: f64 = 0.5;
+/// Chance that fire spreads to each flammable neighbor on a tick
+pub const SPREAD: f64 = 0.5;
+/// Chance that fire burns out into smoke on a tick
+pub const BURN_OUT: f64 = 0.1;
+/// Chance that smoke clears on a tick
+pub const CLEAR: f64 = 0.02;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Sand,
+    Water,
+    Stone,
+    Fire,
+    Smoke,
+    Oil,
+}
+
+auto_cellular::finite_cell!(Cell {
+    Empty => "Empty",
+    Sand => "Sand",
+    Water => "Water",
+    Stone => "Stone",
+    Fire => "Fire",
+    Smoke => "Smoke",
+    Oil => "Oil",
+});
+
+impl Cell {
+    /// Heavier materials sink through lighter ones, and lighter ones rise through heavier
+    /// ones, as long as the one making way [Cell::is_fluid]
+    pub fn density(&self) -> u8 {
+        match self {
+            Cell::Smoke => 0,
+            Cell::Empty | Cell::Fire => 1,
+            Cell::Oil => 8,
+            Cell::Water => 10,
+            Cell::Sand => 16,
+            Cell::Stone => u8::MAX,
+        }
+    }
+
+    /// Whether other materials can move through it
+    pub fn is_fluid(&self) -> bool {
+        matches!(self, Cell::Empty | Cell::Smoke | Cell::Water | Cell::Oil)
+    }
+
+    pub fn is_flammable(&self) -> bool {
+        matches!(self, Cell::Oil)
+    }
+
+    /// Where the material tries to go, in order of preference, given which way is its
+    /// "side" this time
+    fn moves(&self, side: isize) -> Vec<(isize, isize)> {
+        match self {
+            Cell::Sand => vec![(0, 1), (side, 1), (-side, 1)],
+            Cell::Water | Cell::Oil => vec![(0, 1), (side, 1), (-side, 1), (side, 0), (-side, 0)],
+            Cell::Smoke => vec![(0, -1), (side, -1), (-side, -1), (side, 0), (-side, 0)],
+            Cell::Empty | Cell::Stone | Cell::Fire => vec![],
+        }
+    }
+}
+
+impl CellLike for Cell {
+    /// Cycles through the materials
+    fn next_state(&self) -> Self {
+        Self::from_index((self.to_index() + 1) % Self::STATE_COUNT).unwrap()
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        match rng.gen_range(0..20) {
+            0..=1 => Cell::Sand,
+            2..=3 => Cell::Water,
+            4 => Cell::Oil,
+            5 => Cell::Stone,
+            _ => Cell::Empty,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    /// Seed of the random numbers deciding which way materials go
+    pub seed: u64,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// Sand, water, oil, stone, fire and smoke in a box. Unlike the other worlds, cells move
+/// one after another: rows are swept from the bottom up, and a cell that moved this tick
+/// is not moved again
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    rng: StdRng,
+}
+
+impl World {
+    /// Sweeps the whole grid once, drawing from `rng`
+    fn step(&self, rng: &mut StdRng) -> Vec<(Index, Cell)> {
+        let Dimensions(w, h) = self.config.dimensions;
+        let mut cells = self.cells.clone();
+        let mut moved = vec![vec![false; w]; h];
+        for y in (0..h).rev() {
+            // sweeping rows both ways keeps the materials from drifting to one side
+            let backwards: bool = rng.gen();
+            for i in 0..w {
+                let x = if backwards { w - 1 - i } else { i };
+                if !moved[y][x] {
+                    update(&mut cells, &mut moved, (x, y), rng);
+                }
+            }
+        }
+        diff(&self.cells, &cells)
+    }
+}
+
+/// Moves or burns the material at `p`
+fn update(cells: &mut DoubleVec<Cell>, moved: &mut DoubleVec<bool>, p: Index, rng: &mut StdRng) {
+    let (w, h) = (cells[0].len() as isize, cells.len() as isize);
+    let at = |(x, y): Index, (dx, dy): (isize, isize)| {
+        let (x, y) = (x as isize + dx, y as isize + dy);
+        ((0..w).contains(&x) && (0..h).contains(&y)).then_some((x as usize, y as usize))
+    };
+    let (x, y) = p;
+    let cell = cells[y][x];
+
+    match cell {
+        Cell::Fire => {
+            let around: Vec<Index> = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .filter_map(|d| at(p, d))
+                .collect();
+            if around.iter().any(|&(i, j)| cells[j][i] == Cell::Water) {
+                cells[y][x] = Cell::Smoke;
+                return;
+            }
+            for (i, j) in around {
+                if cells[j][i].is_flammable() && rng.gen_bool(SPREAD) {
+                    cells[j][i] = Cell::Fire;
+                    moved[j][i] = true;
+                }
+            }
+            if rng.gen_bool(BURN_OUT) {
+                cells[y][x] = Cell::Smoke;
+            }
+            return;
+        }
+        Cell::Smoke if rng.gen_bool(CLEAR) => {
+            cells[y][x] = Cell::Empty;
+            return;
+        }
+        _ => {}
+    }
+
+    let side = if rng.gen() { 1 } else { -1 };
+    for d @ (_, dy) in cell.moves(side) {
+        let (i, j) = match at(p, d) {
+            Some(q) => q,
+            None => continue,
+        };
+        let other = cells[j][i];
+        let gives_way = !moved[j][i]
+            && other.is_fluid()
+            && match dy {
+                0 => other == Cell::Empty,
+                1 => other.density() < cell.density(),
+                _ => other.density() > cell.density(),
+            };
+        if !gives_way {
+            continue;
+        }
+        if other != Cell::Empty && !rng.gen_bool(VISCOSITY) {
+            return;
+        }
+        cells[j][i] = cell;
+        cells[y][x] = other;
+        moved[j][i] = true;
+        moved[y][x] = true;
+        return;
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        let rng = StdRng::seed_from_u64(config.seed);
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+            rng,
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    /// What the upcoming tick does, which draws the same numbers the tick will
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        self.step(&mut self.rng.clone())
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let mut rng = self.rng.clone();
+        let delta = self.step(&mut rng);
+        for &((x, y), cell) in delta.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.rng = rng;
+        self.delta = delta;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use auto_cellular::cell::histogram;
+
+    use super::*;
+
+    fn world(rows: &[&str], seed: u64) -> World {
+        let cells = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .map(|c| match c {
+                        's' => Cell::Sand,
+                        'w' => Cell::Water,
+                        '#' => Cell::Stone,
+                        'f' => Cell::Fire,
+                        'o' => Cell::Oil,
+                        _ => Cell::Empty,
+                    })
+                    .collect()
+            })
+            .collect();
+        let config = WConfig {
+            dimensions: Dimensions(rows[0].len(), rows.len()),
+            seed,
+        };
+        World::new(cells, config)
+    }
+
+    #[test]
+    fn materials_settle_by_density() {
+        let mut world = world(&["ooo", "www", "sss", "...", "..."], 7);
+        let before = histogram(world.cells());
+        for _ in 0..200 {
+            world.tick();
+        }
+        let rows: Vec<Vec<Cell>> = world.cells().clone();
+        assert_eq!(rows[0], vec![Cell::Empty; 3]);
+        assert_eq!(rows[1], vec![Cell::Empty; 3]);
+        assert_eq!(rows[2], vec![Cell::Oil; 3]);
+        assert_eq!(rows[3], vec![Cell::Water; 3]);
+        assert_eq!(rows[4], vec![Cell::Sand; 3]);
+        assert_eq!(histogram(world.cells()), before);
+    }
+
+    #[test]
+    fn sand_piles_up_and_stone_stays() {
+        let rows = ["..s..", "..s..", "..s..", "..s..", "#...."];
+        let mut world = world(&rows, 1);
+        for _ in 0..20 {
+            world.tick();
+        }
+        let cells = world.cells();
+        assert_eq!(cells[4][0], Cell::Stone);
+        assert_eq!(cells[4][2], Cell::Sand);
+        // no grain is left hanging in the air
+        assert!(cells[..3].iter().flatten().all(|&c| c == Cell::Empty));
+        assert_eq!(histogram(cells)[Cell::Sand.to_index()], 4);
+    }
+
+    #[test]
+    fn fire_burns_oil_and_ticks_are_repeatable() {
+        let mut first = world(&["......", "......", "foooo.", "######"], 3);
+        let mut second = world(&["......", "......", "foooo.", "######"], 3);
+        for _ in 0..10 {
+            let changes = first.changes();
+            first.tick();
+            second.tick();
+            assert_eq!(first.delta(), &changes);
+            assert!(first.cells() == second.cells());
+        }
+        for _ in 0..1000 {
+            first.tick();
+        }
+        let counts = histogram(first.cells());
+        assert!(counts[Cell::Oil.to_index()] < 4);
+        assert_eq!(counts[Cell::Fire.to_index()], 0);
+        assert_eq!(counts[Cell::Smoke.to_index()], 0);
+        assert_eq!(counts[Cell::Stone.to_index()], 6);
+    }
+}
@@ -0,0 +1,210 @@
+use auto_cellular::{
+    cell::{CellLike, Census},
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::neighborhood::{count_around, Neighborhood};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Tree,
+    Burning,
+}
+
+auto_cellular::finite_cell!(Cell {
+    Empty => "Empty",
+    Tree => "Tree",
+    Burning => "Burning",
+});
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        match self {
+            Cell::Empty => Cell::Tree,
+            Cell::Tree => Cell::Burning,
+            Cell::Burning => Cell::Empty,
+        }
+    }
+
+    /// A forest about half grown, with nothing burning yet
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        if rng.gen_bool(0.5) {
+            Cell::Tree
+        } else {
+            Cell::Empty
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    /// Chance that a tree grows on an empty cell every tick
+    pub growth: f64,
+    /// Chance that lightning sets a tree on fire every tick
+    pub lightning: f64,
+    /// Which neighbors of a burning tree catch fire
+    pub neighborhood: Neighborhood,
+    /// Seed of the random numbers the probabilities are drawn against
+    pub seed: u64,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// The Drossel-Schwabl forest fire: trees grow on empty cells, catch fire from burning
+/// neighbors or lightning, and burn down to empty cells
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    offsets: Vec<(isize, isize)>,
+    rng: StdRng,
+    census: Census,
+}
+
+impl World {
+    /// How many cells were empty, trees and burning over the last ticks, see [Census]
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    fn step(&self, rng: &mut StdRng) -> Vec<(Index, Cell)> {
+        let WConfig {
+            growth, lightning, ..
+        } = self.config;
+        let mut delta = vec![];
+        for (j, row) in self.cells.iter().enumerate() {
+            for (i, &cell) in row.iter().enumerate() {
+                let next = match cell {
+                    Cell::Empty if rng.gen_bool(growth) => Cell::Tree,
+                    Cell::Tree => {
+                        let fire = count_around(&self.cells, &self.offsets, (i, j), |c| {
+                            c == Cell::Burning
+                        });
+                        if fire > 0 || rng.gen_bool(lightning) {
+                            Cell::Burning
+                        } else {
+                            Cell::Tree
+                        }
+                    }
+                    Cell::Burning => Cell::Empty,
+                    _ => cell,
+                };
+                if next != cell {
+                    delta.push(((i, j), next));
+                }
+            }
+        }
+        delta
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        let mut census = Census::new();
+        census.record(&cells);
+        Self {
+            cells,
+            offsets: config.neighborhood.offsets(1),
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            delta: linearize(clone),
+            census,
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    /// What the upcoming tick does, which draws the same numbers the tick will
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        self.step(&mut self.rng.clone())
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let mut rng = self.rng.clone();
+        let delta = self.step(&mut rng);
+        for &((x, y), cell) in delta.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.rng = rng;
+        self.census.record(&self.cells);
+        self.delta = delta;
+    }
+
+    /// Painted cells are counted in the census of the last tick
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.census.revise(&self.cells);
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fire_sweeps_through_the_forest() {
+        let config = WConfig {
+            dimensions: Dimensions(9, 9),
+            growth: 0.0,
+            lightning: 0.0,
+            neighborhood: Neighborhood::VonNeumann,
+            seed: 0,
+        };
+        let mut cells = vec![vec![Cell::Tree; 9]; 9];
+        cells[4][4] = Cell::Burning;
+        let mut world = World::new(cells, config);
+        assert_eq!(world.changes().len(), 5);
+        for _ in 0..10 {
+            world.tick();
+        }
+
+        // the front is a diamond, so the corners are the last to go at 8 steps away
+        let burning = world.census().series(Cell::Burning);
+        assert_eq!(burning[..4], [1, 4, 8, 12]);
+        assert_eq!(burning[8..], [4, 0, 0]);
+        assert_eq!(world.census().series(Cell::Empty)[10], 81);
+
+        // clicks and paint are counted without adding a tick
+        world.click((0, 0));
+        world.set_cells(vec![((1, 0), Cell::Burning)]);
+        assert_eq!(world.census().rows().count(), 11);
+        assert_eq!(
+            world.census().summary::<Cell>(),
+            "Empty 79, Tree 1, Burning 1"
+        );
+    }
+}
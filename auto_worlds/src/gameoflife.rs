@@ -14,6 +14,11 @@ pub enum Cell {
     Dead,
 }
 
+auto_cellular::finite_cell!(Cell {
+    Dead => "Dead",
+    Alive => "Alive",
+});
+
 impl CellLike for Cell {
     fn next_state(&self) -> Self {
         match *self {
@@ -0,0 +1,290 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+use crate::{lifelike, PROPORTION};
+
+/// Well known Generations rules that can be asked for by name
+pub const PRESETS: [(&str, &str); 8] = [
+    ("briansbrain", "B2/S/C3"),
+    ("starwars", "B2/S345/C4"),
+    ("frogs", "B34/S12/C3"),
+    ("bloomerang", "B34678/S234/C24"),
+    ("brain6", "B246/S6/C3"),
+    ("sticks", "B2/S3456/C6"),
+    ("transers", "B26/S345/C5"),
+    ("lava", "B45678/S12345/C8"),
+];
+
+/// A cell of a Generations world. State 0 is dead, 1 is alive and every state after
+/// that is one more step of decay towards being dead again
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    state: u8,
+    states: u8,
+}
+
+impl Cell {
+    pub fn new(state: u8, states: u8) -> Self {
+        Self {
+            state: state % states,
+            states,
+        }
+    }
+
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    /// How many states the world this cell lives in has
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.state == 1
+    }
+
+    pub fn is_dying(&self) -> bool {
+        self.state > 1
+    }
+
+    /// 1 for an alive cell, falling towards 0 as the cell decays, and 0 for a dead one
+    pub fn level(&self) -> f64 {
+        if self.state == 0 {
+            0.0
+        } else {
+            (self.states - self.state) as f64 / (self.states - 1) as f64
+        }
+    }
+
+    fn with_states(self, states: u8) -> Self {
+        Self::new(self.state, states)
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(0, 2)
+    }
+}
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        Self::new(self.state + 1, self.states)
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        if rng.gen_bool(PROPORTION) {
+            Self::default()
+        } else {
+            Self::new(1, 2)
+        }
+    }
+}
+
+/// A Life-like birth/survival rule where cells that fail to survive take
+/// `states - 2` ticks to die, during which they do not count as alive neighbors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub life: lifelike::Rule,
+    pub states: u8,
+}
+
+impl Rule {
+    /// The state of a cell next tick given its alive neighbor count
+    pub fn next(&self, cell: Cell, count: usize) -> Cell {
+        let state = match cell.state {
+            0 if self.life.birth[count] => 1,
+            0 => 0,
+            1 if self.life.survival[count] => 1,
+            s => s + 1,
+        };
+        Cell::new(state, self.states)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        "B2/S/C3".parse().unwrap()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses `B2/S/C3` style rulestrings (in any order, `G` works as well as `C`), the
+    /// legacy `/2/3` form (survival, birth, states) and the names in [PRESETS]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((_, rule)) = PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return rule.parse();
+        }
+
+        let parts: Vec<String> = s.split('/').map(|p| p.to_ascii_uppercase()).collect();
+        if parts.len() != 3 {
+            return Err(format!("`{}` should have three parts", s));
+        }
+
+        let find = |prefixes: &[char]| {
+            parts
+                .iter()
+                .find_map(|p| p.strip_prefix(prefixes).map(String::from))
+        };
+        let (birth, survival, states) = match (find(&['B']), find(&['S']), find(&['C', 'G'])) {
+            (Some(b), Some(s), Some(c)) => (b, s, c),
+            _ => (parts[1].clone(), parts[0].clone(), parts[2].clone()),
+        };
+
+        let states: u8 = states
+            .parse()
+            .map_err(|_| format!("`{}` is not a number of states", states))?;
+        if states < 2 {
+            return Err(String::from("a Generations rule needs at least 2 states"));
+        }
+
+        Ok(Self {
+            life: lifelike::Rule {
+                birth: lifelike::counts(&birth)?,
+                survival: lifelike::counts(&survival)?,
+            },
+            states,
+        })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/C{}", self.life, self.states)
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    /// Cells are brought to the number of states of the rule
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let states = config.rule.states;
+        let cells: DoubleVec<Cell> = cells
+            .into_iter()
+            .map(|row| row.into_iter().map(|c| c.with_states(states)).collect())
+            .collect();
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+        let rule = &self.config().rule;
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                let cell = self.cells[j][i];
+                let count = self
+                    .moore_neighbors(p)
+                    .iter()
+                    .filter(|c| self.cells[c.1][c.0].is_alive())
+                    .count();
+                let next = rule.next(cell, count);
+                if next != cell {
+                    delta.push((p, next));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    /// Painted cells are brought to the number of states of the rule
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        let states = self.config.rule.states;
+        let changes: Vec<(Index, Cell)> = changes
+            .into_iter()
+            .map(|(i, c)| (i, c.with_states(states)))
+            .collect();
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_rulestrings() {
+        let brain: Rule = "B2/S/C3".parse().unwrap();
+        assert_eq!(brain.states, 3);
+        assert!(brain.life.birth[2]);
+        assert!(!brain.life.survival.contains(&true));
+
+        assert_eq!("/2/3".parse::<Rule>(), Ok(brain));
+        assert_eq!("c3/b2/s".parse::<Rule>(), Ok(brain));
+        assert_eq!("BriansBrain".parse::<Rule>(), Ok(brain));
+        assert_eq!("345/2/4".parse::<Rule>().unwrap().to_string(), "B2/S345/C4");
+        assert!("B2/S/C1".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn cells_decay() {
+        let rule: Rule = "B2/S/C4".parse().unwrap();
+        let alive = Cell::new(1, 4);
+        let dying = rule.next(alive, 5);
+        assert_eq!(dying.state(), 2);
+        assert!((dying.level() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(rule.next(rule.next(dying, 0), 0).state(), 0);
+        assert_eq!(rule.next(Cell::new(0, 4), 2), alive);
+    }
+}
@@ -0,0 +1,367 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+use crate::lenia::{fields, number};
+
+/// Well known feed and kill rates that can be asked for by name
+pub const PRESETS: [(&str, &str); 3] = [
+    ("mitosis", "f=0.0367;k=0.0649"),
+    ("coral", "f=0.0545;k=0.062"),
+    ("spots", "f=0.035;k=0.065"),
+];
+
+/// How far around a painted cell chemical B is seeded
+pub const SEED_RADIUS: isize = 3;
+
+/// Concentrations of the two chemicals, between 0 and 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Cell {
+    /// Values outside of `0..=1` are clamped
+    pub fn new(a: f32, b: f32) -> Self {
+        Self {
+            a: a.clamp(0.0, 1.0),
+            b: b.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Where some B was dropped
+    pub fn seeded() -> Self {
+        Self::new(0.5, 0.25)
+    }
+}
+
+impl Default for Cell {
+    /// All A and no B
+    fn default() -> Self {
+        Self::new(1.0, 0.0)
+    }
+}
+
+impl CellLike for Cell {
+    /// Seeds B, or clears it from a seeded cell
+    fn next_state(&self) -> Self {
+        if self.b > 0.0 {
+            Self::default()
+        } else {
+            Self::seeded()
+        }
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        if rng.gen_bool(0.01) {
+            Self::seeded()
+        } else {
+            Self::default()
+        }
+    }
+}
+
+/// The weights of the neighbors in the discrete Laplacian. They add up to 1 in both, as
+/// the cell itself weighs -1, so the same diffusion rates work for either
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Stencil {
+    /// The 4 orthogonal neighbors weigh 1/4
+    Five,
+    /// The 4 orthogonal neighbors weigh 1/5 and the 4 diagonal ones 1/20
+    #[default]
+    Nine,
+}
+
+impl Stencil {
+    pub fn weights(&self) -> Vec<((isize, isize), f32)> {
+        let orthogonal = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+        let diagonal = [(1, 1), (-1, 1), (1, -1), (-1, -1)];
+        let mut weights = vec![];
+        match self {
+            Stencil::Five => weights.extend(orthogonal.map(|d| (d, 0.25))),
+            Stencil::Nine => {
+                weights.extend(orthogonal.map(|d| (d, 0.2)));
+                weights.extend(diagonal.map(|d| (d, 0.05)));
+            }
+        }
+        weights
+    }
+}
+
+impl FromStr for Stencil {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "5" => Ok(Self::Five),
+            "9" => Ok(Self::Nine),
+            _ => Err(format!("`{}` is not a stencil, try 5 or 9", s)),
+        }
+    }
+}
+
+impl Display for Stencil {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stencil::Five => write!(f, "5"),
+            Stencil::Nine => write!(f, "9"),
+        }
+    }
+}
+
+/// Gray-Scott rates, written like `f=0.0367;k=0.0649;da=1;db=0.5;dt=1;stencil=9`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    /// How fast A is fed in
+    pub feed: f32,
+    /// How fast B is taken out
+    pub kill: f32,
+    pub diffusion_a: f32,
+    pub diffusion_b: f32,
+    pub dt: f32,
+    pub stencil: Stencil,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        PRESETS[0].1.parse().unwrap()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses the notation of [Rule], where only the feed and kill rates are required, or
+    /// one of the names in [PRESETS]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((_, rule)) = PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s.trim()))
+        {
+            return rule.parse();
+        }
+
+        let fields = fields(s)?;
+        let field = |key: &str, default: Option<f32>| match (fields.get(key), default) {
+            (Some(value), _) => number(value),
+            (None, Some(default)) => Ok(default),
+            (None, None) => Err(format!("`{}` is missing `{}`", s, key)),
+        };
+        if let Some(key) = fields
+            .keys()
+            .find(|key| !["f", "k", "da", "db", "dt", "stencil"].contains(&&key[..]))
+        {
+            return Err(format!("unknown field `{}`", key));
+        }
+        Ok(Self {
+            feed: field("f", None)?,
+            kill: field("k", None)?,
+            diffusion_a: field("da", Some(1.0))?,
+            diffusion_b: field("db", Some(0.5))?,
+            dt: field("dt", Some(1.0))?,
+            stencil: match fields.get("stencil") {
+                Some(stencil) => stencil.parse()?,
+                None => Stencil::default(),
+            },
+        })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "f={};k={};da={};db={};dt={};stencil={}",
+            self.feed, self.kill, self.diffusion_a, self.diffusion_b, self.dt, self.stencil
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// Gray-Scott reaction-diffusion: A is fed in, B is taken out, both diffuse, and B
+/// turns A into more B
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    stencil: Vec<((isize, isize), f32)>,
+}
+
+impl World {
+    /// The Laplacian of both chemicals at `(x, y)`, wrapping around the edges
+    fn laplacian(&self, (x, y): Index) -> (f32, f32) {
+        let Dimensions(w, h) = self.config.dimensions;
+        let center = self.cells[y][x];
+        self.stencil
+            .iter()
+            .fold((0.0, 0.0), |(a, b), ((dx, dy), weight)| {
+                let i = (x as isize + dx).rem_euclid(w as isize) as usize;
+                let j = (y as isize + dy).rem_euclid(h as isize) as usize;
+                let cell = self.cells[j][i];
+                (
+                    a + (cell.a - center.a) * weight,
+                    b + (cell.b - center.b) * weight,
+                )
+            })
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        Self {
+            cells,
+            stencil: config.rule.stencil.weights(),
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    /// A few seeds of B dropped at random, see [World::set_cells]
+    fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        let Dimensions(w, h) = config.dimensions;
+        let mut world = Self::new_blank(config);
+        let seeds = (0..w * h / 400 + 1)
+            .map(|_| ((rng.gen_range(0..w), rng.gen_range(0..h)), Cell::seeded()))
+            .collect();
+        world.set_cells(seeds);
+        world.delta = linearize(world.cells.clone());
+        world
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let rule = &self.config.rule;
+        let mut delta = vec![];
+        for (j, row) in self.cells.iter().enumerate() {
+            for (i, &cell) in row.iter().enumerate() {
+                let (lap_a, lap_b) = self.laplacian((i, j));
+                let reaction = cell.a * cell.b * cell.b;
+                let next = Cell::new(
+                    cell.a
+                        + rule.dt
+                            * (rule.diffusion_a * lap_a - reaction + rule.feed * (1.0 - cell.a)),
+                    cell.b
+                        + rule.dt
+                            * (rule.diffusion_b * lap_b + reaction
+                                - (rule.kill + rule.feed) * cell.b),
+                );
+                if next != cell {
+                    delta.push(((i, j), next));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    /// Seeding B covers everything within [SEED_RADIUS] of the painted cell, since a
+    /// single cell of it is washed away before it can react
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        let Dimensions(w, h) = self.config.dimensions;
+        let mut spread = vec![];
+        for ((x, y), cell) in changes {
+            if cell.b == 0.0 {
+                spread.push(((x, y), cell));
+                continue;
+            }
+            let r = SEED_RADIUS;
+            for dy in -r..=r {
+                for dx in -r..=r {
+                    if dx * dx + dy * dy <= r * r {
+                        let i = (x as isize + dx).rem_euclid(w as isize) as usize;
+                        let j = (y as isize + dy).rem_euclid(h as isize) as usize;
+                        spread.push(((i, j), cell));
+                    }
+                }
+            }
+        }
+        for &((x, y), cell) in spread.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.delta = spread;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_rules() {
+        let mitosis = Rule::default();
+        assert_eq!((mitosis.feed, mitosis.kill), (0.0367, 0.0649));
+        assert_eq!(mitosis.stencil, Stencil::Nine);
+        assert_eq!(mitosis.to_string().parse(), Ok(mitosis));
+        let rule: Rule = "f=0.03;k=0.06;stencil=5".parse().unwrap();
+        assert_eq!(rule.stencil, Stencil::Five);
+        assert!("k=0.06".parse::<Rule>().is_err());
+        assert!("f=0.03;k=0.06;x=1".parse::<Rule>().is_err());
+        for stencil in [Stencil::Five, Stencil::Nine] {
+            let total: f32 = stencil.weights().iter().map(|(_, w)| w).sum();
+            assert!((total - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn reacts_where_seeded() {
+        let config = WConfig {
+            dimensions: Dimensions(12, 12),
+            rule: "coral".parse().unwrap(),
+        };
+        let mut world = World::new_blank(config.clone());
+        assert!(world.changes().is_empty());
+
+        // without neighbors to diffuse to, only the reaction is left
+        let mut seeded = World::new(vec![vec![Cell::seeded(); 12]; 12], config);
+        seeded.tick();
+        let (a, b) = (0.5f32, 0.25f32);
+        let (f, k) = (0.0545, 0.062);
+        let expected = Cell::new(a - a * b * b + f * (1.0 - a), b + a * b * b - (k + f) * b);
+        let cell = seeded.cells()[3][3];
+        assert!((cell.a - expected.a).abs() < 1e-6 && (cell.b - expected.b).abs() < 1e-6);
+
+        world.set_cells(vec![((0, 0), Cell::seeded())]);
+        assert_eq!(world.delta().len(), 29);
+        assert_eq!(world.cells()[0][11], Cell::seeded());
+        world.tick();
+        assert!(world.cells()[0][SEED_RADIUS as usize + 1].b > 0.0);
+    }
+}
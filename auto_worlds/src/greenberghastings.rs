@@ -0,0 +1,152 @@
+use auto_cellular::{
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+pub use crate::cyclic::{Cell, Neighborhood, Rule};
+use crate::{cyclic::soup, neighborhood::count_around};
+
+/// Excitable media worth a look that can be asked for by name
+pub const PRESETS: [(&str, &str); 4] = [
+    ("classic", "R1/T1/C3/NN"),
+    ("moore", "R1/T1/C3/NM"),
+    ("slow", "R1/T1/C8/NN"),
+    ("wide", "R3/T4/C6/NM"),
+];
+
+/// Parses a [Rule] written the same way as cyclic ones, or one of the names in [PRESETS]
+pub fn rule(s: &str) -> Result<Rule, String> {
+    Rule::parse_with(s, &PRESETS)
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// State 0 is resting, 1 is excited and the rest are refractory. A resting cell gets
+/// excited once at least `threshold` of its neighbors are, every other cell moves on
+/// to the next state on its own, and the last refractory state goes back to resting
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    offsets: Vec<(isize, isize)>,
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    /// Cells are brought to the number of states of the rule
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let states = config.rule.states;
+        let cells: DoubleVec<Cell> = cells
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|c| Cell::new(c.state(), states))
+                    .collect()
+            })
+            .collect();
+        let clone = cells.clone();
+        Self {
+            cells,
+            offsets: config.rule.neighborhood.offsets(config.rule.range),
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    /// Every state is equally likely
+    fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        let cells = soup(rng, config.dimensions, config.rule.states);
+        Self::new(cells, config)
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+        let rule = &self.config().rule;
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                let cell = self.cells[j][i];
+                if cell.state() > 0
+                    || count_around(&self.cells, &self.offsets, p, |c| c.is_alive())
+                        >= rule.threshold
+                {
+                    delta.push((p, Cell::new(cell.state() + 1, rule.states)));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    /// Painted cells are brought to the number of states of the rule
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        let states = self.config.rule.states;
+        let changes: Vec<(Index, Cell)> = changes
+            .into_iter()
+            .map(|(i, c)| (i, Cell::new(c.state(), states)))
+            .collect();
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn waves_spread_and_leave_refractory_cells_behind() {
+        let config = WConfig {
+            dimensions: Dimensions(7, 1),
+            rule: rule("R1/T1/C4/NN").unwrap(),
+        };
+        let mut cells = vec![vec![Cell::new(0, 4); 7]];
+        cells[0][3] = Cell::new(1, 4);
+        let mut world = World::new(cells, config);
+
+        let states =
+            |world: &World| -> Vec<u8> { world.cells()[0].iter().map(|c| c.state()).collect() };
+        world.tick();
+        assert_eq!(states(&world), [0, 0, 1, 2, 1, 0, 0]);
+        world.tick();
+        assert_eq!(states(&world), [0, 1, 2, 3, 2, 1, 0]);
+        // refractory cells ignore their excited neighbors, so the wave only moves outwards
+        world.tick();
+        assert_eq!(states(&world), [1, 2, 3, 0, 3, 2, 1]);
+        assert_eq!(rule("classic").unwrap().to_string(), "R1/T1/C3/NN");
+    }
+}
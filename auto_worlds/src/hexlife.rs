@@ -0,0 +1,170 @@
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, Index},
+    hex::Topology,
+    world::{WorldConfig, WorldLike},
+};
+
+pub use crate::{gameoflife::Cell, lifelike::Rule, wolfram::Seed};
+
+/// Hexagonal rules worth a look that can be asked for by name
+pub const PRESETS: [(&str, &str); 3] = [
+    ("hexlife", "B2/S34"),
+    // a cell with exactly one alive neighbor is born and nothing dies, which grows a
+    // snowflake out of a single cell
+    ("snowflake", "B1/S0123456"),
+    ("hexseeds", "B2/S"),
+];
+
+/// Parses a Life-like rulestring or one of the names in [PRESETS]. Counts above 6 are
+/// never reached with 6 neighbors
+pub fn rule(s: &str) -> Result<Rule, String> {
+    match PRESETS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(s.trim()))
+    {
+        Some((_, rule)) => rule.parse(),
+        None => s.parse(),
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+    pub seed: Seed,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn topology(&self) -> Topology {
+        Topology::Hex
+    }
+}
+
+/// A Life-like rule on a grid of hexagons, counting the 6 neighbors of every cell
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    /// A single alive cell in the middle, or a random soup, depending on the seed
+    fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        let Dimensions(width, height) = config.dimensions;
+        let cells = match config.seed {
+            Seed::Single => {
+                let mut cells = vec![vec![Cell::Dead; width]; height];
+                // an empty grid has no middle to seed
+                if let Some(middle) = cells
+                    .get_mut(height / 2)
+                    .and_then(|row| row.get_mut(width / 2))
+                {
+                    *middle = Cell::Alive;
+                }
+                cells
+            }
+            Seed::Random => (0..height)
+                .map(|_| (0..width).map(|_| Cell::random(rng)).collect())
+                .collect(),
+        };
+        Self::new(cells, config)
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+        let rule = &self.config().rule;
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                let alive = matches!(self.cells[j][i], Cell::Alive);
+                let count = self
+                    .neighbors(p)
+                    .iter()
+                    .filter(|c| matches!(self.cells[c.1][c.0], Cell::Alive))
+                    .count();
+                let next = rule.next(alive, count);
+                if next != alive {
+                    delta.push((p, if next { Cell::Alive } else { Cell::Dead }));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snowflakes_grow_in_six_directions() {
+        let config = WConfig {
+            dimensions: Dimensions(12, 12),
+            rule: rule("snowflake").unwrap(),
+            seed: Seed::Single,
+        };
+        let mut world = World::new_random(&mut rand::thread_rng(), config);
+        world.tick();
+
+        // the seed's 6 neighbors are born, and nothing else
+        let mut alive: Vec<Index> = linearize(world.cells().clone())
+            .into_iter()
+            .filter(|(_, c)| matches!(c, Cell::Alive))
+            .map(|(i, _)| i)
+            .collect();
+        let mut expected = world.neighbors((6, 6));
+        expected.push((6, 6));
+        alive.sort();
+        expected.sort();
+        assert_eq!(alive, expected);
+        assert_eq!(world.config().topology(), Topology::Hex);
+
+        for dimensions in [Dimensions(0, 4), Dimensions(4, 0)] {
+            let empty = WConfig {
+                dimensions,
+                rule: rule("snowflake").unwrap(),
+                seed: Seed::Single,
+            };
+            let world = World::new_random(&mut rand::thread_rng(), empty);
+            assert!(world.cells().iter().flatten().next().is_none());
+        }
+    }
+}
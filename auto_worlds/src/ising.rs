@@ -0,0 +1,314 @@
+use std::str::FromStr;
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{diff, linearize, Dimensions, DoubleVec, History, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The critical temperature of the 2D Ising model with a coupling of 1 and no field
+pub const CRITICAL_TEMPERATURE: f64 = 2.269_185_314;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    Up,
+    Down,
+}
+
+auto_cellular::finite_cell!(Cell {
+    Up => "Up",
+    Down => "Down",
+});
+
+impl Cell {
+    /// +1 or -1
+    pub fn spin(&self) -> i32 {
+        match self {
+            Cell::Up => 1,
+            Cell::Down => -1,
+        }
+    }
+}
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        match self {
+            Cell::Up => Cell::Down,
+            Cell::Down => Cell::Up,
+        }
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        if rng.gen() {
+            Cell::Up
+        } else {
+            Cell::Down
+        }
+    }
+}
+
+/// How a spin is updated given its neighbors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dynamics {
+    /// Flips whenever that lowers the energy, and otherwise with the Boltzmann factor
+    #[default]
+    Metropolis,
+    /// Picks the spin afresh from its equilibrium distribution given its neighbors
+    HeatBath,
+}
+
+impl FromStr for Dynamics {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "metropolis" => Ok(Self::Metropolis),
+            "heatbath" | "heat-bath" | "glauber" => Ok(Self::HeatBath),
+            _ => Err(format!("unknown dynamics `{}`", s)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub temperature: f64,
+    /// Positive for neighbors to align, negative for them to alternate
+    pub coupling: f64,
+    /// External field, which favors up spins when positive
+    pub field: f64,
+    pub dynamics: Dynamics,
+    /// Seed of the random numbers the flips are drawn against
+    pub seed: u64,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// What the lattice looks like after a sweep, per spin
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Measurement {
+    /// Mean spin, between -1 and 1
+    pub magnetization: f64,
+    pub energy: f64,
+}
+
+/// Mean spin and energy per spin of a lattice, counting every bond once
+pub fn measure(cells: &DoubleVec<Cell>, coupling: f64, field: f64) -> Measurement {
+    let (w, h) = (cells[0].len(), cells.len());
+    let (mut spins, mut bonds) = (0, 0);
+    for y in 0..h {
+        for x in 0..w {
+            let s = cells[y][x].spin();
+            spins += s;
+            bonds += s * (cells[y][(x + 1) % w].spin() + cells[(y + 1) % h][x].spin());
+        }
+    }
+    let n = (w * h) as f64;
+    Measurement {
+        magnetization: spins as f64 / n,
+        energy: (-coupling * bonds as f64 - field * spins as f64) / n,
+    }
+}
+
+/// The Ising model on a toroidal square lattice. A tick is a sweep over a checkerboard:
+/// first every black spin is updated, then every white one, and as no two spins of a
+/// color are neighbors each half is updated at once. Dimensions should be even for the
+/// colors to alternate across the edges
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    rng: StdRng,
+    measurements: History<Measurement>,
+}
+
+impl World {
+    /// The lattice at creation and after every sweep, as far back as the [History] goes
+    pub fn measurements(&self) -> &History<Measurement> {
+        &self.measurements
+    }
+
+    pub fn temperature(&self) -> f64 {
+        self.config.temperature
+    }
+
+    /// Takes effect from the next sweep. Temperatures below 0 are taken as 0
+    pub fn set_temperature(&mut self, temperature: f64) {
+        self.config.temperature = temperature.max(0.0);
+    }
+
+    fn step(&self, rng: &mut StdRng) -> DoubleVec<Cell> {
+        let Dimensions(w, h) = self.config.dimensions;
+        let WConfig {
+            coupling,
+            field,
+            dynamics,
+            ..
+        } = self.config;
+        // at 0 only the sign of the energy matters
+        let temperature = self.config.temperature.max(f64::MIN_POSITIVE);
+        let mut cells = self.cells.clone();
+
+        for color in [0, 1] {
+            for y in 0..h {
+                for x in (0..w).filter(|x| (x + y) % 2 == color) {
+                    let around = cells[y][(x + 1) % w].spin()
+                        + cells[y][(x + w - 1) % w].spin()
+                        + cells[(y + 1) % h][x].spin()
+                        + cells[(y + h - 1) % h][x].spin();
+                    // what the spin feels from its neighbors and the field
+                    let local = coupling * around as f64 + field;
+                    let spin = cells[y][x];
+                    cells[y][x] = match dynamics {
+                        Dynamics::Metropolis => {
+                            let cost = 2.0 * spin.spin() as f64 * local;
+                            if cost <= 0.0 || rng.gen::<f64>() < (-cost / temperature).exp() {
+                                spin.next_state()
+                            } else {
+                                spin
+                            }
+                        }
+                        Dynamics::HeatBath => {
+                            let up = 1.0 / (1.0 + (-2.0 * local / temperature).exp());
+                            if rng.gen::<f64>() < up {
+                                Cell::Up
+                            } else {
+                                Cell::Down
+                            }
+                        }
+                    };
+                }
+            }
+        }
+        cells
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        let mut measurements = History::new();
+        measurements.push(measure(&cells, config.coupling, config.field));
+        Self {
+            cells,
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            delta: linearize(clone),
+            measurements,
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    /// What the upcoming sweep does, which draws the same numbers the sweep will
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        diff(&self.cells, &self.step(&mut self.rng.clone()))
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let mut rng = self.rng.clone();
+        let cells = self.step(&mut rng);
+        self.delta = diff(&self.cells, &cells);
+        self.cells = cells;
+        self.rng = rng;
+        let measurement = measure(&self.cells, self.config.coupling, self.config.field);
+        self.measurements.push(measurement);
+    }
+
+    /// Flipped spins are measured again as part of the last sweep
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        let measurement = measure(&self.cells, self.config.coupling, self.config.field);
+        self.measurements.revise(measurement);
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(temperature: f64, field: f64, dynamics: Dynamics) -> WConfig {
+        WConfig {
+            dimensions: Dimensions(16, 16),
+            temperature,
+            coupling: 1.0,
+            field,
+            dynamics,
+            seed: 3,
+        }
+    }
+
+    #[test]
+    fn measures_aligned_lattices() {
+        let up = vec![vec![Cell::Up; 4]; 4];
+        let measurement = measure(&up, 1.0, 0.5);
+        assert_eq!(measurement.magnetization, 1.0);
+        assert_eq!(measurement.energy, -2.5);
+
+        // every bond of a checkerboard is against the coupling
+        let checkerboard: DoubleVec<Cell> = (0..4)
+            .map(|y| {
+                (0..4)
+                    .map(|x| [Cell::Up, Cell::Down][(x + y) % 2])
+                    .collect()
+            })
+            .collect();
+        let measurement = measure(&checkerboard, 1.0, 0.0);
+        assert_eq!((measurement.magnetization, measurement.energy), (0.0, 2.0));
+    }
+
+    #[test]
+    fn order_depends_on_the_temperature() {
+        for dynamics in [Dynamics::Metropolis, Dynamics::HeatBath] {
+            // a strong field aligns everything in a single sweep
+            let mut world = World::new_blank(config(1.0, 10.0, dynamics));
+            world.set_cells(vec![((3, 3), Cell::Down), ((4, 3), Cell::Down)]);
+            assert_eq!(world.measurements().len(), 1);
+            assert!(world.measurements()[0].magnetization < 1.0);
+            world.tick();
+            assert_eq!(world.measurements()[1].magnetization, 1.0);
+
+            let mut cold = World::new_blank(config(1.0, 0.0, dynamics));
+            let mut hot = World::new_blank(config(100.0, 0.0, dynamics));
+            for _ in 0..50 {
+                cold.tick();
+                hot.tick();
+            }
+            assert!(cold.measurements()[50].magnetization > 0.9);
+            assert!(hot.measurements()[50].magnetization.abs() < 0.3);
+
+            hot.set_temperature(-1.0);
+            assert_eq!(hot.temperature(), 0.0);
+        }
+    }
+}
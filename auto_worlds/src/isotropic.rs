@@ -0,0 +1,355 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+pub use crate::gameoflife::Cell;
+use crate::lifelike;
+
+/// Well known isotropic non-totalistic rules that can be asked for by name
+pub const PRESETS: [(&str, &str); 2] = [("tlife", "B3/S2-i34q"), ("justfriends", "B2-a/S12")];
+
+/// A neighborhood is 9 bits in reading order, the cell itself being bit 4
+const CENTER: usize = 1 << 4;
+/// All 8 neighbors, without the cell itself
+const NEIGHBORS: usize = 0b111_101_111;
+
+/// Where every bit goes when the neighborhood is turned a quarter clockwise
+const ROTATE: [usize; 9] = [2, 5, 8, 1, 4, 7, 0, 3, 6];
+/// Where every bit goes when the neighborhood is mirrored left to right
+const MIRROR: [usize; 9] = [2, 1, 0, 5, 4, 3, 8, 7, 6];
+
+/// Hensel's letters for every neighbor count up to 4, followed by one neighborhood of
+/// each. Counts above 4 use the letters of `8 - count`, with the neighborhoods inverted
+const LETTERS: [(&str, &[usize]); 5] = [
+    ("", &[0]),
+    ("ce", &[1, 2]),
+    ("ceaikn", &[5, 10, 3, 40, 33, 68]),
+    ("ceaiknjqry", &[69, 42, 11, 7, 98, 13, 14, 70, 41, 97]),
+    (
+        "ceaiknjqrytwz",
+        &[325, 170, 15, 45, 99, 71, 106, 102, 43, 101, 105, 78, 108],
+    ),
+];
+
+fn permute(mask: usize, to: &[usize; 9]) -> usize {
+    (0..9)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .fold(0, |acc, bit| acc | (1 << to[bit]))
+}
+
+/// The letters of a neighbor count, each with all the rotations and reflections
+/// of its neighborhood
+fn configurations(count: usize) -> Vec<(char, Vec<usize>)> {
+    let (letters, masks) = LETTERS[count.min(8 - count)];
+    let letters: Vec<char> = if letters.is_empty() {
+        vec![' ']
+    } else {
+        letters.chars().collect()
+    };
+
+    letters
+        .into_iter()
+        .zip(masks.iter())
+        .map(|(letter, &mask)| {
+            let mask = if count > 4 { NEIGHBORS ^ mask } else { mask };
+            let mut orbit = vec![];
+            for mirrored in [mask, permute(mask, &MIRROR)] {
+                let mut m = mirrored;
+                for _ in 0..4 {
+                    orbit.push(m);
+                    m = permute(m, &ROTATE);
+                }
+            }
+            orbit.sort_unstable();
+            orbit.dedup();
+            (letter, orbit)
+        })
+        .collect()
+}
+
+/// A rule that looks at the exact arrangement of the 8 neighbors, up to rotations and
+/// reflections, written in Hensel notation like `B2-a/S12`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    /// Whether a cell is alive next tick, indexed by its whole neighborhood
+    pub table: [bool; 512],
+}
+
+impl Rule {
+    /// Whether a cell will be alive given its neighborhood, in reading order
+    pub fn next(&self, neighborhood: usize) -> bool {
+        self.table[neighborhood]
+    }
+
+    fn set(&mut self, alive: bool, neighbors: usize) {
+        let center = if alive { CENTER } else { 0 };
+        self.table[neighbors | center] = true;
+    }
+
+    fn has(&self, alive: bool, neighbors: usize) -> bool {
+        let center = if alive { CENTER } else { 0 };
+        self.table[neighbors | center]
+    }
+}
+
+impl std::fmt::Debug for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rule({})", self)
+    }
+}
+
+impl From<lifelike::Rule> for Rule {
+    fn from(rule: lifelike::Rule) -> Self {
+        let mut table = [false; 512];
+        for (mask, next) in table.iter_mut().enumerate() {
+            let count = (mask & NEIGHBORS).count_ones() as usize;
+            *next = rule.next(mask & CENTER != 0, count);
+        }
+        Self { table }
+    }
+}
+
+/// Parses the part of a rulestring after `B` or `S`, e.g. `2-a3ce`
+fn parse_half(rule: &mut Rule, alive: bool, s: &str) -> Result<(), String> {
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = match c.to_digit(10) {
+            Some(n @ 0..=8) => n as usize,
+            _ => return Err(format!("`{}` is not a neighbor count", c)),
+        };
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut letters = vec![];
+        while let Some(l) = chars.next_if(|l| l.is_ascii_alphabetic()) {
+            letters.push(l);
+        }
+
+        let configurations = configurations(count);
+        for &l in letters.iter() {
+            if !configurations.iter().any(|(letter, _)| *letter == l) {
+                return Err(format!("{} neighbors have no configuration `{}`", count, l));
+            }
+        }
+        for (letter, orbit) in configurations {
+            if letters.is_empty() || letters.contains(&letter) != negated {
+                for &neighbors in orbit.iter() {
+                    rule.set(alive, neighbors);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses `B2-a/S12` style rulestrings (in either order), the names in [PRESETS] and
+    /// anything [lifelike::Rule] accepts, since totalistic rules are a special case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((_, rule)) = PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return rule.parse();
+        }
+        if let Ok(rule) = s.parse::<lifelike::Rule>() {
+            return Ok(rule.into());
+        }
+
+        let (first, second) = s
+            .split_once('/')
+            .ok_or_else(|| format!("`{}` is missing a `/`", s))?;
+        let (birth, survival) = match (
+            first.strip_prefix(['B', 'b']),
+            second.strip_prefix(['S', 's']),
+        ) {
+            (Some(b), Some(s)) => (b, s),
+            _ => match (
+                first.strip_prefix(['S', 's']),
+                second.strip_prefix(['B', 'b']),
+            ) {
+                (Some(s), Some(b)) => (b, s),
+                _ => return Err(format!("`{}` should look like `B2-a/S12`", s)),
+            },
+        };
+
+        let mut rule = Self {
+            table: [false; 512],
+        };
+        parse_half(&mut rule, false, &birth.to_ascii_lowercase())?;
+        parse_half(&mut rule, true, &survival.to_ascii_lowercase())?;
+        Ok(rule)
+    }
+}
+
+impl Display for Rule {
+    /// Writes the shortest form of every count, e.g. `3-a` instead of `3ceiknjqry`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let half = |alive: bool| -> String {
+            let mut out = String::new();
+            for count in 0..=8 {
+                let configurations = configurations(count);
+                let (on, off): (Vec<_>, Vec<_>) = configurations
+                    .iter()
+                    .partition(|(_, orbit)| self.has(alive, orbit[0]));
+                let letters = |c: Vec<&(char, Vec<usize>)>| -> String {
+                    c.into_iter().map(|(l, _)| *l).collect()
+                };
+                let digit = char::from_digit(count as u32, 10).unwrap();
+                match (on.is_empty(), off.is_empty()) {
+                    // no configuration of the count is on, so the count is left out
+                    (true, _) => {}
+                    (false, true) => out.push(digit),
+                    (false, false) if on.len() <= off.len() => {
+                        out.push(digit);
+                        out.push_str(&letters(on));
+                    }
+                    (false, false) => {
+                        out.push(digit);
+                        out.push('-');
+                        out.push_str(&letters(off));
+                    }
+                }
+            }
+            out
+        };
+        write!(f, "B{}/S{}", half(false), half(true))
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+}
+
+impl World {
+    /// The 9 bit neighborhood of the cell at (x, y), in reading order
+    fn neighborhood(&self, (x, y): Index) -> usize {
+        let (w, h) = (
+            self.config.dimensions.0 as isize,
+            self.config.dimensions.1 as isize,
+        );
+        let mut mask = 0;
+        for (bit, (dx, dy)) in (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .enumerate()
+        {
+            let nx = (x as isize + dx).rem_euclid(w) as usize;
+            let ny = (y as isize + dy).rem_euclid(h) as usize;
+            if matches!(self.cells[ny][nx], Cell::Alive) {
+                mask |= 1 << bit;
+            }
+        }
+        mask
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+        let rule = &self.config().rule;
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                let alive = matches!(self.cells[j][i], Cell::Alive);
+                match (alive, rule.next(self.neighborhood(p))) {
+                    (true, false) => delta.push((p, Cell::Dead)),
+                    (false, true) => delta.push((p, Cell::Alive)),
+                    _ => {}
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn letters_cover_every_neighborhood_once() {
+        let mut seen = [0; 512];
+        for count in 0..=8 {
+            for (_, orbit) in configurations(count) {
+                for neighbors in orbit {
+                    assert_eq!((neighbors & NEIGHBORS).count_ones() as usize, count);
+                    seen[neighbors] += 1;
+                }
+            }
+        }
+        for (mask, times) in seen.iter().enumerate() {
+            assert_eq!(*times, (mask & CENTER == 0) as usize);
+        }
+    }
+
+    #[test]
+    fn parses_hensel_notation() {
+        let friends: Rule = "B2-a/S12".parse().unwrap();
+        // N and NE touch, which is 2a
+        assert!(!friends.next(0b000_000_110));
+        // N and S are opposite, which is 2i
+        assert!(friends.next(0b010_000_010));
+        assert!(friends.next(CENTER | 0b000_000_001));
+        assert!(!friends.next(CENTER | 0b000_000_111));
+        assert_eq!(friends.to_string(), "B2-a/S12");
+
+        let life: Rule = "B3/S23".parse().unwrap();
+        assert_eq!(life, Rule::from(lifelike::Rule::default()));
+        assert_eq!(life.to_string(), "B3/S23");
+        assert_eq!("tlife".parse::<Rule>().unwrap().to_string(), "B3/S2-i34q");
+
+        assert!("B1a/S".parse::<Rule>().is_err());
+        assert!("B2-a".parse::<Rule>().is_err());
+    }
+}
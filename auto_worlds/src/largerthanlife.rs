@@ -0,0 +1,367 @@
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
+
+use auto_cellular::{
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+pub use crate::{generations::Cell, neighborhood::Neighborhood};
+
+/// Well known Larger than Life rules that can be asked for by name
+pub const PRESETS: [(&str, &str); 4] = [
+    ("bosco", "R5,C0,M1,S34..58,B34..45,NM"),
+    ("majority", "R4,C0,M1,S41..81,B41..81,NM"),
+    ("waffle", "R7,C0,M1,S100..200,B75..170,NM"),
+    ("globe", "R8,C0,M0,S163..223,B74..252,NM"),
+];
+
+/// A range-R totalistic rule, written like `R5,C0,M1,S34..58,B34..45,NM`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub radius: usize,
+    /// Number of states, where anything above 2 makes cells decay like in Generations
+    pub states: u8,
+    /// Whether a cell counts itself as its own neighbor
+    pub middle: bool,
+    pub survival: RangeInclusive<usize>,
+    pub birth: RangeInclusive<usize>,
+    pub neighborhood: Neighborhood,
+}
+
+impl Rule {
+    /// The state of a cell next tick given its alive neighbor count
+    pub fn next(&self, cell: Cell, count: usize) -> Cell {
+        let state = match cell.state() {
+            0 if self.birth.contains(&count) => 1,
+            0 => 0,
+            1 if self.survival.contains(&count) => 1,
+            s => s + 1,
+        };
+        Cell::new(state, self.states)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        PRESETS[0].1.parse().unwrap()
+    }
+}
+
+fn range(s: &str) -> Result<RangeInclusive<usize>, String> {
+    let number = |n: &str| {
+        n.parse::<usize>()
+            .map_err(|_| format!("`{}` is not a neighbor count", n))
+    };
+    match s.split_once("..") {
+        Some((min, max)) => {
+            let (min, max) = (number(min)?, number(max)?);
+            if min > max {
+                return Err(format!("the range `{}` is empty", s));
+            }
+            Ok(min..=max)
+        }
+        None => number(s).map(|n| n..=n),
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses rulestrings like `R5,C0,M1,S34..58,B34..45,NM` and the names in [PRESETS].
+    /// The neighborhood may be left out, in which case it is Moore
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((_, rule)) = PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return rule.parse();
+        }
+
+        let (mut radius, mut states, mut middle) = (None, None, None);
+        let (mut survival, mut birth) = (None, None);
+        let mut neighborhood = Neighborhood::Moore;
+        for part in s.split(',') {
+            let part = part.trim().to_ascii_uppercase();
+            let mut chars = part.chars();
+            let key = chars.next().ok_or("empty part in rulestring")?;
+            let value = chars.as_str();
+            let bad = || format!("`{}` is not a valid part", part);
+            match key {
+                'R' => radius = Some(value.parse::<usize>().map_err(|_| bad())?),
+                'C' => states = Some(value.parse::<u8>().map_err(|_| bad())?),
+                'M' => {
+                    middle = match value {
+                        "0" => Some(false),
+                        "1" => Some(true),
+                        _ => return Err(bad()),
+                    }
+                }
+                'S' => survival = Some(range(value)?),
+                'B' => birth = Some(range(value)?),
+                'N' => {
+                    neighborhood = match value {
+                        "M" => Neighborhood::Moore,
+                        "N" => Neighborhood::VonNeumann,
+                        _ => return Err(bad()),
+                    }
+                }
+                _ => return Err(bad()),
+            }
+        }
+
+        let missing = |what: &str| format!("`{}` is missing {}", s, what);
+        let radius = radius.ok_or_else(|| missing("the radius `R`"))?;
+        if radius == 0 {
+            return Err(String::from("the radius has to be at least 1"));
+        }
+        Ok(Self {
+            radius,
+            // C0 and C1 are how two states are usually written
+            states: states.ok_or_else(|| missing("the states `C`"))?.max(2),
+            middle: middle.ok_or_else(|| missing("the middle `M`"))?,
+            survival: survival.ok_or_else(|| missing("the survival range `S`"))?,
+            birth: birth.ok_or_else(|| missing("the birth range `B`"))?,
+            neighborhood,
+        })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},N{}",
+            self.radius,
+            self.states,
+            self.middle as u8,
+            self.survival.start(),
+            self.survival.end(),
+            self.birth.start(),
+            self.birth.end(),
+            match self.neighborhood {
+                Neighborhood::Moore => 'M',
+                Neighborhood::VonNeumann => 'N',
+            }
+        )
+    }
+}
+
+/// Summed-area table of alive cells over the grid padded by `radius` on every side
+/// with its own wrapped around edges, so any box around a cell is four lookups
+struct SummedArea {
+    table: DoubleVec<usize>,
+}
+
+impl SummedArea {
+    fn new(cells: &DoubleVec<Cell>, radius: usize) -> Self {
+        let width = cells.first().map_or(0, |row| row.len());
+        if width == 0 {
+            // there is no cell to count around
+            return Self { table: vec![] };
+        }
+        let (w, h) = (width as isize, cells.len() as isize);
+        let r = radius as isize;
+        let (pw, ph) = (width + 2 * radius, cells.len() + 2 * radius);
+
+        let mut table = vec![vec![0; pw + 1]; ph + 1];
+        for y in 0..ph {
+            let row = &cells[(y as isize - r).rem_euclid(h) as usize];
+            for x in 0..pw {
+                let alive = row[(x as isize - r).rem_euclid(w) as usize].is_alive() as usize;
+                table[y + 1][x + 1] = alive + table[y][x + 1] + table[y + 1][x] - table[y][x];
+            }
+        }
+        Self { table }
+    }
+
+    /// Alive cells in the padded box from (x0, y0) to (x1, y1), both included
+    fn sum(&self, (x0, y0): Index, (x1, y1): Index) -> usize {
+        self.table[y1 + 1][x1 + 1] + self.table[y0][x0]
+            - self.table[y0][x1 + 1]
+            - self.table[y1 + 1][x0]
+    }
+
+    /// Alive cells in the neighborhood of the cell at (x, y) of the original grid
+    fn count(&self, (x, y): Index, radius: usize, neighborhood: Neighborhood) -> usize {
+        // (x, y) of the grid is (x + radius, y + radius) in the padded grid
+        match neighborhood {
+            Neighborhood::Moore => self.sum((x, y), (x + 2 * radius, y + 2 * radius)),
+            Neighborhood::VonNeumann => (0..=2 * radius)
+                .map(|dy| {
+                    let reach = radius - dy.abs_diff(radius);
+                    let row = y + dy;
+                    self.sum((x + radius - reach, row), (x + radius + reach, row))
+                })
+                .sum(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    /// Cells are brought to the number of states of the rule
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let states = config.rule.states;
+        let cells: DoubleVec<Cell> = cells
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|c| Cell::new(c.state(), states))
+                    .collect()
+            })
+            .collect();
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+        let rule = &self.config().rule;
+        let table = SummedArea::new(&self.cells, rule.radius);
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                let cell = self.cells[j][i];
+                let mut count = table.count(p, rule.radius, rule.neighborhood);
+                if !rule.middle && cell.is_alive() {
+                    count -= 1;
+                }
+                let next = rule.next(cell, count);
+                if next != cell {
+                    delta.push((p, next));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    /// Painted cells are brought to the number of states of the rule
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        let states = self.config.rule.states;
+        let changes: Vec<(Index, Cell)> = changes
+            .into_iter()
+            .map(|(i, c)| (i, Cell::new(c.state(), states)))
+            .collect();
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_rulestrings() {
+        let bosco: Rule = "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap();
+        assert_eq!(bosco.radius, 5);
+        assert_eq!(bosco.states, 2);
+        assert!(bosco.middle);
+        assert_eq!(bosco.survival, 34..=58);
+        assert_eq!(bosco.birth, 34..=45);
+        assert_eq!(bosco.to_string(), "R5,C2,M1,S34..58,B34..45,NM");
+        assert_eq!("Bosco".parse::<Rule>(), Ok(bosco.clone()));
+        assert_eq!(bosco.to_string().parse::<Rule>(), Ok(bosco));
+
+        let diamond: Rule = "R2,C3,M0,S2..3,B3,NN".parse().unwrap();
+        assert_eq!(diamond.neighborhood, Neighborhood::VonNeumann);
+        assert_eq!(diamond.birth, 3..=3);
+        assert!("R0,C0,M1,S1,B1".parse::<Rule>().is_err());
+        assert!("R2,C0,S1,B1".parse::<Rule>().is_err());
+        assert!("R2,C0,M2,S1,B1".parse::<Rule>().is_err());
+        assert!("R2,C0,Myes,S1,B1".parse::<Rule>().is_err());
+        assert!("R2,C0,M1,S5..3,B1".parse::<Rule>().is_err());
+
+        for (_, preset) in PRESETS {
+            let rule: Rule = preset.parse().unwrap();
+            assert_eq!(rule.to_string().parse::<Rule>(), Ok(rule));
+        }
+    }
+
+    #[test]
+    fn summed_area_counts_match_brute_force() {
+        let (w, h, r) = (7usize, 5usize, 2usize);
+        let cells: DoubleVec<Cell> = (0..h)
+            .map(|y| {
+                (0..w)
+                    .map(|x| Cell::new(((x * 3 + y * 5) % 4 == 0) as u8, 2))
+                    .collect()
+            })
+            .collect();
+        let table = SummedArea::new(&cells, r);
+
+        for y in 0..h {
+            for x in 0..w {
+                let (mut moore, mut diamond) = (0, 0);
+                for dy in -(r as isize)..=r as isize {
+                    for dx in -(r as isize)..=r as isize {
+                        let c = cells[(y as isize + dy).rem_euclid(h as isize) as usize]
+                            [(x as isize + dx).rem_euclid(w as isize) as usize];
+                        moore += c.is_alive() as usize;
+                        if dx.abs() + dy.abs() <= r as isize {
+                            diamond += c.is_alive() as usize;
+                        }
+                    }
+                }
+                assert_eq!(table.count((x, y), r, Neighborhood::Moore), moore);
+                assert_eq!(table.count((x, y), r, Neighborhood::VonNeumann), diamond);
+            }
+        }
+
+        for dimensions in [Dimensions(0, 0), Dimensions(0, 3), Dimensions(3, 0)] {
+            let config = WConfig {
+                dimensions,
+                rule: Rule::default(),
+            };
+            assert!(World::new_blank(config).changes().is_empty());
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+/// Well known rules that can be asked for by name
+pub const PRESETS: [(&str, &str); 2] = [
+    ("orbium", "R=13;dt=0.1;m=0.15;s=0.015;b=1;g=gaussian"),
+    (
+        "hydrogeminium",
+        "R=18;dt=0.1;m=0.26;s=0.036;b=1/2,1,2/3;g=gaussian",
+    ),
+];
+
+/// A cell whose state is anywhere between 0 and 1
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Cell {
+    pub value: f32,
+}
+
+impl Cell {
+    /// Values outside of `0..=1` are clamped
+    pub fn new(value: f32) -> Self {
+        Self {
+            value: value.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl CellLike for Cell {
+    /// Fully on, or off when it was mostly on already
+    fn next_state(&self) -> Self {
+        Self::new(if self.value < 0.5 { 1.0 } else { 0.0 })
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(rng.gen())
+    }
+}
+
+/// A grid of random cells in a box half the size of the world around its middle, and
+/// empty elsewhere
+pub(crate) fn patch<R: rand::Rng + ?Sized>(
+    rng: &mut R,
+    Dimensions(w, h): Dimensions,
+) -> DoubleVec<Cell> {
+    (0..h)
+        .map(|j| {
+            (0..w)
+                .map(|i| {
+                    let inside = (w / 4..w - w / 4).contains(&i) && (h / 4..h - h / 4).contains(&j);
+                    if inside {
+                        Cell::random(rng)
+                    } else {
+                        Cell::default()
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Weights of the cells at some offsets around a cell
+pub type Kernel = Vec<((isize, isize), f32)>;
+
+/// The weighted sum of the cells around `(x, y)`, wrapping around the edges
+pub(crate) fn convolve(cells: &DoubleVec<Cell>, kernel: &Kernel, (x, y): Index) -> f32 {
+    let (w, h) = (cells[0].len() as isize, cells.len() as isize);
+    kernel
+        .iter()
+        .map(|((dx, dy), weight)| {
+            let i = (x as isize + dx).rem_euclid(w) as usize;
+            let j = (y as isize + dy).rem_euclid(h) as usize;
+            cells[j][i].value * weight
+        })
+        .sum()
+}
+
+/// Splits `key=value` pairs separated by `;`
+pub(crate) fn fields(s: &str) -> Result<HashMap<String, String>, String> {
+    s.split(';')
+        .filter(|field| !field.trim().is_empty())
+        .map(|field| {
+            field
+                .split_once('=')
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .ok_or_else(|| format!("`{}` should look like `key=value`", field))
+        })
+        .collect()
+}
+
+/// A decimal number or a fraction like `2/3`
+pub(crate) fn number(s: &str) -> Result<f32, String> {
+    let invalid = || format!("`{}` is not a number", s);
+    match s.split_once('/') {
+        Some((a, b)) => {
+            let (a, b): (f32, f32) = (
+                a.trim().parse().map_err(|_| invalid())?,
+                b.trim().parse().map_err(|_| invalid())?,
+            );
+            Ok(a / b)
+        }
+        None => s.trim().parse().map_err(|_| invalid()),
+    }
+}
+
+/// Checks that `value` is above 0, which widths that get divided by have to be
+pub(crate) fn positive(key: &str, value: f32) -> Result<(), String> {
+    if value > 0.0 {
+        Ok(())
+    } else {
+        Err(format!("`{}` should be above 0, not {}", key, value))
+    }
+}
+
+/// Checks that `dt` is a time step above 0 and up to a whole tick
+pub(crate) fn time_step(dt: f32) -> Result<(), String> {
+    if dt > 0.0 && dt <= 1.0 {
+        Ok(())
+    } else {
+        Err(format!("`dt` should be above 0 and at most 1, not {}", dt))
+    }
+}
+
+/// How the potential of a cell (its convolution with the kernel) makes it grow or
+/// shrink, peaking at `mu` and `sigma` wide
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Growth {
+    #[default]
+    Gaussian,
+    Polynomial,
+    Step,
+}
+
+impl Growth {
+    /// Between -1 and 1
+    pub fn apply(&self, u: f32, mu: f32, sigma: f32) -> f32 {
+        let d = u - mu;
+        match self {
+            Growth::Gaussian => 2.0 * (-d * d / (2.0 * sigma * sigma)).exp() - 1.0,
+            Growth::Polynomial => {
+                2.0 * (1.0 - d * d / (9.0 * sigma * sigma)).max(0.0).powi(4) - 1.0
+            }
+            Growth::Step => {
+                if d.abs() <= sigma {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Growth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "gaussian" => Ok(Self::Gaussian),
+            "polynomial" => Ok(Self::Polynomial),
+            "step" => Ok(Self::Step),
+            _ => Err(format!("unknown growth function `{}`", s)),
+        }
+    }
+}
+
+impl Display for Growth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Growth::Gaussian => "gaussian",
+            Growth::Polynomial => "polynomial",
+            Growth::Step => "step",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A Lenia rule, written like `R=13;dt=0.1;m=0.15;s=0.015;b=1;g=gaussian`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    /// Radius of the kernel, in cells
+    pub radius: usize,
+    /// How much of the growth is taken every tick
+    pub dt: f32,
+    pub mu: f32,
+    pub sigma: f32,
+    /// Heights of the concentric rings of the kernel, from the inside out
+    pub peaks: Vec<f32>,
+    pub growth: Growth,
+}
+
+impl Rule {
+    /// Smooth concentric rings, each a bump that is highest halfway through it, weighed
+    /// to sum to 1
+    pub fn kernel(&self) -> Kernel {
+        let r = self.radius as isize;
+        let rings = self.peaks.len() as f32;
+        let mut kernel: Kernel = (-r..=r)
+            .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+            .filter_map(|(dx, dy)| {
+                let distance = ((dx * dx + dy * dy) as f32).sqrt() / self.radius as f32;
+                if distance >= 1.0 {
+                    return None;
+                }
+                let ring = distance * rings;
+                let (index, within) = (ring as usize, ring.fract());
+                // the exponential bump, which is 0 at both edges of a ring
+                let bump = if within > 0.0 {
+                    (4.0 - 1.0 / (within * (1.0 - within))).exp()
+                } else {
+                    0.0
+                };
+                let weight = self.peaks[index] * bump;
+                (weight > 0.0).then_some(((dx, dy), weight))
+            })
+            .collect();
+        let total: f32 = kernel.iter().map(|(_, w)| w).sum();
+        for (_, weight) in kernel.iter_mut() {
+            *weight /= total;
+        }
+        kernel
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        PRESETS[0].1.parse().unwrap()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses the notation of [Rule], or one of the names in [PRESETS]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((_, rule)) = PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s.trim()))
+        {
+            return rule.parse();
+        }
+
+        let fields = fields(s)?;
+        let field = |key: &str| {
+            fields
+                .get(key)
+                .ok_or_else(|| format!("`{}` is missing `{}`", s, key))
+        };
+        let rule = Self {
+            radius: field("R")?
+                .parse()
+                .map_err(|_| format!("`{}` is not a radius", fields["R"]))?,
+            dt: number(field("dt")?)?,
+            mu: number(field("m")?)?,
+            sigma: number(field("s")?)?,
+            peaks: field("b")?
+                .split(',')
+                .map(number)
+                .collect::<Result<_, _>>()?,
+            growth: match fields.get("g") {
+                Some(growth) => growth.parse()?,
+                None => Growth::default(),
+            },
+        };
+        if rule.radius == 0 || rule.peaks.iter().all(|&peak| peak <= 0.0) {
+            return Err(format!("`{}` has an empty kernel", s));
+        }
+        positive("s", rule.sigma)?;
+        time_step(rule.dt)?;
+        Ok(rule)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let peaks: Vec<String> = self.peaks.iter().map(f32::to_string).collect();
+        write!(
+            f,
+            "R={};dt={};m={};s={};b={};g={}",
+            self.radius,
+            self.dt,
+            self.mu,
+            self.sigma,
+            peaks.join(","),
+            self.growth
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// Lenia: every cell grows by `dt` times the growth function of its potential, the sum
+/// of the cells around it weighed by the kernel
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    kernel: Kernel,
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        Self {
+            cells,
+            kernel: config.rule.kernel(),
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    /// A random patch in the middle, since a whole random world is too crowded
+    fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        Self::new(patch(rng, config.dimensions), config)
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let Rule {
+            dt,
+            mu,
+            sigma,
+            growth,
+            ..
+        } = self.config.rule;
+        let mut delta = vec![];
+        for (j, row) in self.cells.iter().enumerate() {
+            for (i, &cell) in row.iter().enumerate() {
+                let potential = convolve(&self.cells, &self.kernel, (i, j));
+                let next = Cell::new(cell.value + dt * growth.apply(potential, mu, sigma));
+                if next != cell {
+                    delta.push(((i, j), next));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_rules_and_builds_kernels() {
+        let orbium = Rule::default();
+        assert_eq!((orbium.radius, orbium.peaks.clone()), (13, vec![1.0]));
+        assert_eq!(orbium.to_string().parse(), Ok(orbium.clone()));
+        let rule: Rule = "hydrogeminium".parse().unwrap();
+        assert_eq!(rule.peaks[0], 0.5);
+        assert!("R=13;dt=0.1".parse::<Rule>().is_err());
+        assert!("R=0;dt=0.1;m=0.1;s=0.1;b=1".parse::<Rule>().is_err());
+        for bad in ["s=0", "s=-0.1", "s=NaN", "dt=0", "dt=1.5", "dt=NaN"] {
+            let rule = format!("R=13;dt=0.1;m=0.15;s=0.015;b=1;{}", bad);
+            assert!(rule.parse::<Rule>().is_err(), "{} parsed", bad);
+        }
+
+        let kernel = orbium.kernel();
+        let total: f32 = kernel.iter().map(|(_, w)| w).sum();
+        assert!((total - 1.0).abs() < 1e-4);
+        // a ring, so the middle and the outside are left out
+        assert!(kernel.iter().all(|((dx, dy), _)| (dx, dy) != (&0, &0)));
+        assert!(kernel
+            .iter()
+            .all(|((dx, dy), _)| dx * dx + dy * dy < 13 * 13));
+    }
+
+    #[test]
+    fn cells_grow_by_dt_at_the_peak() {
+        let rule: Rule = "R=3;dt=0.25;m=0.4;s=0.1;b=1;g=step".parse().unwrap();
+        let config = WConfig {
+            dimensions: Dimensions(8, 8),
+            rule,
+        };
+        // a uniform world has its own value as potential
+        let mut world = World::new(vec![vec![Cell::new(0.4); 8]; 8], config.clone());
+        let all = |world: &World, value: f32| {
+            let close = |c: &Cell| (c.value - value).abs() < 1e-5;
+            world.cells().iter().flatten().all(close)
+        };
+        world.tick();
+        assert!(all(&world, 0.65));
+        world.tick();
+        assert!(all(&world, 0.4));
+
+        let mut empty = World::new_blank(config);
+        empty.tick();
+        assert!(empty.delta().is_empty());
+    }
+}
@@ -1,8 +1,31 @@
 use std::{fmt::Display, str::FromStr};
 
 pub mod briansbrain;
+pub mod cyclic;
+pub mod fallingsand;
+pub mod forestfire;
 pub mod gameoflife;
-pub mod langtonsant;
+pub mod generations;
+pub mod grayscott;
+pub mod greenberghastings;
+pub mod hexlife;
+pub mod ising;
+pub mod isotropic;
+pub mod largerthanlife;
+pub mod lenia;
+pub mod lifelike;
+pub mod margolus;
+pub mod neighborhood;
+pub mod ruletable;
+pub mod sandpile;
+pub mod schelling;
+pub mod sir;
+pub mod smoothlife;
+pub mod turmite;
+pub mod voter;
+pub mod wator;
+pub mod wireworld;
+pub mod wolfram;
 
 pub const PROPORTION: f64 = 0.9;
 
@@ -10,13 +33,56 @@ pub const PROPORTION: f64 = 0.9;
 pub enum WorldKind {
     GameOfLife,
     BriansBrain,
-    LangtonsAnt,
+    Turmite,
+    LifeLike,
+    Generations,
+    LargerThanLife,
+    Wolfram,
+    WireWorld,
+    Cyclic,
+    GreenbergHastings,
+    HexLife,
+    RuleTable,
+    Margolus,
+    FallingSand,
+    ForestFire,
+    Sir,
+    Sandpile,
+    Lenia,
+    SmoothLife,
+    GrayScott,
+    WaTor,
+    Ising,
+    Schelling,
+    Voter,
 }
 
 pub enum World {
     GameOfLife(gameoflife::World),
     BriansBrain(briansbrain::World),
-    LangtonsAnt(langtonsant::world::World),
+    Turmite(turmite::World),
+    LifeLike(lifelike::World),
+    Generations(generations::World),
+    LargerThanLife(largerthanlife::World),
+    Isotropic(Box<isotropic::World>),
+    Wolfram(wolfram::World),
+    WireWorld(wireworld::World),
+    Cyclic(cyclic::World),
+    GreenbergHastings(greenberghastings::World),
+    HexLife(hexlife::World),
+    RuleTable(ruletable::World),
+    Margolus(margolus::World),
+    FallingSand(fallingsand::World),
+    ForestFire(forestfire::World),
+    Sir(sir::World),
+    Sandpile(sandpile::World),
+    Lenia(lenia::World),
+    SmoothLife(smoothlife::World),
+    GrayScott(grayscott::World),
+    WaTor(wator::World),
+    Ising(ising::World),
+    Schelling(schelling::World),
+    Voter(voter::World),
 }
 
 impl Display for WorldKind {
@@ -31,7 +97,32 @@ impl FromStr for WorldKind {
         match s {
             "gof" | "Game of Life" | "GameOfLife" => Ok(Self::GameOfLife),
             "bb" | "Brian's Brian " | "BriansBrian" => Ok(Self::BriansBrain),
-            "la" | "Langton's Ant" | "LangtonsAnt" => Ok(Self::LangtonsAnt),
+            "tm" | "turmite" | "Turmite" | "la" | "Langton's Ant" | "LangtonsAnt" => {
+                Ok(Self::Turmite)
+            }
+            "ll" | "life" | "Life-like" | "LifeLike" => Ok(Self::LifeLike),
+            "gen" | "generations" | "Generations" => Ok(Self::Generations),
+            "ltl" | "Larger than Life" | "LargerThanLife" => Ok(Self::LargerThanLife),
+            "1d" | "wolfram" | "elementary" | "Wolfram" => Ok(Self::Wolfram),
+            "ww" | "WireWorld" | "wireworld" => Ok(Self::WireWorld),
+            "cyclic" | "cca" | "Cyclic" => Ok(Self::Cyclic),
+            "gh" | "excitable" | "greenberghastings" | "GreenbergHastings" => {
+                Ok(Self::GreenbergHastings)
+            }
+            "hex" | "hexlife" | "HexLife" => Ok(Self::HexLife),
+            "table" | "golly" | "ruletable" | "RuleTable" => Ok(Self::RuleTable),
+            "margolus" | "block" | "Margolus" => Ok(Self::Margolus),
+            "sand" | "fallingsand" | "FallingSand" => Ok(Self::FallingSand),
+            "fire" | "forestfire" | "ForestFire" => Ok(Self::ForestFire),
+            "sir" | "epidemic" | "SIR" | "Sir" => Ok(Self::Sir),
+            "sandpile" | "abelian" | "Sandpile" => Ok(Self::Sandpile),
+            "lenia" | "Lenia" => Ok(Self::Lenia),
+            "smooth" | "smoothlife" | "SmoothLife" => Ok(Self::SmoothLife),
+            "gs" | "grayscott" | "reaction" | "GrayScott" => Ok(Self::GrayScott),
+            "wator" | "wa-tor" | "WaTor" => Ok(Self::WaTor),
+            "ising" | "spins" | "Ising" => Ok(Self::Ising),
+            "schelling" | "segregation" | "Schelling" => Ok(Self::Schelling),
+            "voter" | "majority" | "opinion" | "Voter" => Ok(Self::Voter),
             _ => Err(String::from("unknown")),
         }
     }
@@ -0,0 +1,205 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+pub use crate::gameoflife::Cell;
+
+/// Well known Life-like rules that can be asked for by name
+pub const PRESETS: [(&str, &str); 10] = [
+    ("life", "B3/S23"),
+    ("highlife", "B36/S23"),
+    ("seeds", "B2/S"),
+    ("daynight", "B3678/S34678"),
+    ("maze", "B3/S12345"),
+    ("replicator", "B1357/S1357"),
+    ("diamoeba", "B35678/S5678"),
+    ("2x2", "B36/S125"),
+    ("morley", "B368/S245"),
+    ("lifewithoutdeath", "B3/S012345678"),
+];
+
+/// A totalistic rule on the Moore neighborhood: whether a cell is alive next tick only
+/// depends on whether it is alive now and how many of its 8 neighbors are
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// `birth[n]` is true if a dead cell with `n` alive neighbors is born
+    pub birth: [bool; 9],
+    /// `survival[n]` is true if an alive cell with `n` alive neighbors survives
+    pub survival: [bool; 9],
+}
+
+impl Rule {
+    /// Whether a cell will be alive given its current state and alive neighbor count
+    pub fn next(&self, alive: bool, count: usize) -> bool {
+        if alive {
+            self.survival[count]
+        } else {
+            self.birth[count]
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        "B3/S23".parse().unwrap()
+    }
+}
+
+pub(crate) fn counts(digits: &str) -> Result<[bool; 9], String> {
+    let mut table = [false; 9];
+    for c in digits.chars() {
+        match c.to_digit(10) {
+            Some(n @ 0..=8) => table[n as usize] = true,
+            _ => return Err(format!("`{}` is not a neighbor count", c)),
+        }
+    }
+    Ok(table)
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses `B3/S23` style rulestrings (in either order), the legacy `23/3` form
+    /// (survival first) and the names in [PRESETS]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((_, rule)) = PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return rule.parse();
+        }
+
+        let (first, second) = s
+            .split_once('/')
+            .ok_or_else(|| format!("`{}` is missing a `/`", s))?;
+        let upper = |part: &str| part.to_ascii_uppercase();
+        let (first, second) = (upper(first), upper(second));
+
+        let (birth, survival) = match (first.strip_prefix('B'), second.strip_prefix('S')) {
+            (Some(b), Some(s)) => (b.to_string(), s.to_string()),
+            _ => match (first.strip_prefix('S'), second.strip_prefix('B')) {
+                (Some(s), Some(b)) => (b.to_string(), s.to_string()),
+                _ => (second, first),
+            },
+        };
+
+        Ok(Self {
+            birth: counts(&birth)?,
+            survival: counts(&survival)?,
+        })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = |table: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| table[n])
+                .map(|n| char::from_digit(n as u32, 10).unwrap())
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+        let rule = &self.config().rule;
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                let count = self
+                    .moore_neighbors(p)
+                    .iter()
+                    .filter(|c| matches!(self.cells[c.1][c.0], Cell::Alive))
+                    .count();
+                let alive = matches!(self.cells[j][i], Cell::Alive);
+                match (alive, rule.next(alive, count)) {
+                    (true, false) => delta.push((p, Cell::Dead)),
+                    (false, true) => delta.push((p, Cell::Alive)),
+                    _ => {}
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_rulestrings() {
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert!(highlife.birth[3] && highlife.birth[6] && !highlife.birth[2]);
+        assert!(highlife.survival[2] && highlife.survival[3]);
+
+        assert_eq!("23/3".parse::<Rule>(), "B3/S23".parse());
+        assert_eq!("s23/b3".parse::<Rule>(), "B3/S23".parse());
+        assert_eq!("Seeds".parse::<Rule>().unwrap().to_string(), "B2/S");
+        assert_eq!(
+            "B3678/S34678".parse::<Rule>().unwrap().to_string(),
+            "B3678/S34678"
+        );
+
+        assert!("B9/S23".parse::<Rule>().is_err());
+        assert!("B3S23".parse::<Rule>().is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+pub use crate::gameoflife::Cell;
+
+/// Well known block rules that can be asked for by name
+pub const PRESETS: [(&str, &str); 4] = [
+    ("bbm", "MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15"),
+    ("critters", "MS,D15;14;13;3;11;5;6;1;7;9;10;2;12;4;8;0"),
+    ("tron", "MS,D15;1;2;3;4;5;6;7;8;9;10;11;12;13;14;0"),
+    ("sand", "MS,D0;4;8;12;4;12;12;13;8;12;12;14;12;13;14;15"),
+];
+
+/// What every 2x2 block turns into. A block is written as the sum of its alive cells,
+/// counting 1 for the upper left, 2 for the upper right, 4 for the lower left and 8
+/// for the lower right one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub table: [u8; 16],
+}
+
+impl Rule {
+    /// Whether no two blocks turn into the same one, so the rule can be run backwards
+    pub fn is_reversible(&self) -> bool {
+        let mut seen = [false; 16];
+        self.table
+            .iter()
+            .all(|&block| !std::mem::replace(&mut seen[block as usize], true))
+    }
+
+    /// The rule that undoes this one, if it is reversible
+    pub fn inverse(&self) -> Option<Self> {
+        if !self.is_reversible() {
+            return None;
+        }
+        let mut table = [0; 16];
+        for (block, &next) in self.table.iter().enumerate() {
+            table[next as usize] = block as u8;
+        }
+        Some(Self { table })
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        PRESETS[0].1.parse().unwrap()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses 16 blocks separated by `;` or `,`, optionally in MCell's `MS,D...` form,
+    /// and the names in [PRESETS]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((_, rule)) = PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return rule.parse();
+        }
+
+        let upper = s.to_ascii_uppercase();
+        let blocks = upper
+            .strip_prefix("MS,D")
+            .or_else(|| upper.strip_prefix('D'))
+            .unwrap_or(&upper);
+        let blocks = blocks
+            .split([';', ','])
+            .map(|block| match block.trim().parse::<u8>() {
+                Ok(block) if block < 16 => Ok(block),
+                _ => Err(format!("`{}` is not a block", block)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let table = blocks
+            .try_into()
+            .map_err(|_| format!("`{}` should have 16 blocks", s))?;
+        Ok(Self { table })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let blocks: Vec<String> = self.table.iter().map(u8::to_string).collect();
+        write!(f, "MS,D{}", blocks.join(";"))
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// A block cellular automaton on the Margolus neighborhood: the grid is cut into 2x2
+/// blocks that each follow the rule, and the blocks move one cell down and to the right
+/// every other tick (wrapping around the edges). Dimensions should be even, otherwise the
+/// last row or column sits out every block it would have to share with the first
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    /// 0 or 1, the offset of the blocks for the upcoming tick
+    phase: usize,
+}
+
+impl World {
+    pub fn phase(&self) -> usize {
+        self.phase
+    }
+
+    /// The changes `rule` makes to the blocks at the given offset
+    fn blocks(&self, rule: &Rule, phase: usize) -> Vec<(Index, Cell)> {
+        let Dimensions(w, h) = self.config.dimensions;
+        let mut delta = vec![];
+        for by in 0..h / 2 {
+            for bx in 0..w / 2 {
+                let (x0, y0) = (phase + 2 * bx, phase + 2 * by);
+                let corners = [
+                    (x0 % w, y0 % h),
+                    ((x0 + 1) % w, y0 % h),
+                    (x0 % w, (y0 + 1) % h),
+                    ((x0 + 1) % w, (y0 + 1) % h),
+                ];
+                let block = corners
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &(x, y))| matches!(self.cells[y][x], Cell::Alive))
+                    .map(|(bit, _)| 1 << bit)
+                    .sum::<usize>();
+                let next = rule.table[block] as usize;
+                if next == block {
+                    continue;
+                }
+                for (bit, &p) in corners.iter().enumerate() {
+                    if (block ^ next) & (1 << bit) != 0 {
+                        let alive = next & (1 << bit) != 0;
+                        delta.push((p, if alive { Cell::Alive } else { Cell::Dead }));
+                    }
+                }
+            }
+        }
+        delta
+    }
+
+    /// Undoes the last tick, for reversible rules
+    pub fn untick(&mut self) -> Result<(), String> {
+        let inverse = self
+            .config
+            .rule
+            .inverse()
+            .ok_or_else(|| format!("{} is not reversible", self.config.rule))?;
+        let phase = 1 - self.phase;
+        let delta = self.blocks(&inverse, phase);
+        for &((x, y), cell) in delta.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.phase = phase;
+        self.delta = delta;
+        Ok(())
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+            phase: 0,
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        self.blocks(&self.config.rule, self.phase)
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let delta = self.changes();
+        for &((x, y), cell) in delta.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.phase = 1 - self.phase;
+        self.delta = delta;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_rules() {
+        let bbm = Rule::default();
+        assert_eq!(bbm.table[1], 8);
+        assert_eq!(bbm.to_string(), PRESETS[0].1);
+        assert_eq!("0,8,4,3,2,5,9,7,1,6,10,11,12,13,14,15".parse(), Ok(bbm));
+        assert!("critters".parse::<Rule>().unwrap().is_reversible());
+        assert!(!"sand".parse::<Rule>().unwrap().is_reversible());
+        assert!("0;1;2".parse::<Rule>().is_err());
+        assert!("MS,D16;1;2;3;4;5;6;7;8;9;10;11;12;13;14;15"
+            .parse::<Rule>()
+            .is_err());
+    }
+
+    #[test]
+    fn reversible_rules_run_backwards() {
+        let config = WConfig {
+            dimensions: Dimensions(10, 8),
+            rule: "critters".parse().unwrap(),
+        };
+        let mut world = World::new_random(&mut rand::thread_rng(), config);
+        let start = world.cells().clone();
+        for _ in 0..7 {
+            world.tick();
+        }
+        assert_eq!(world.phase(), 1);
+        for _ in 0..7 {
+            world.untick().unwrap();
+        }
+        assert_eq!(world.phase(), 0);
+        assert!(world.cells() == &start);
+
+        let config = WConfig {
+            dimensions: Dimensions(4, 4),
+            rule: "sand".parse().unwrap(),
+        };
+        assert!(World::new_blank(config).untick().is_err());
+    }
+
+    #[test]
+    fn billiard_balls_fly_diagonally() {
+        let config = WConfig {
+            dimensions: Dimensions(8, 8),
+            rule: Rule::default(),
+        };
+        let mut cells = vec![vec![Cell::Dead; 8]; 8];
+        cells[2][2] = Cell::Alive;
+        let mut world = World::new(cells, config);
+        for _ in 0..3 {
+            world.tick();
+        }
+        // the upper left of a block jumps to the lower right, then to the upper left of
+        // the next offset block, and so on
+        assert!(matches!(world.cells()[5][5], Cell::Alive));
+    }
+}
@@ -0,0 +1,44 @@
+use auto_cellular::common::{DoubleVec, Index};
+
+/// Which cells within the radius count as neighbors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The square of side `2R + 1`
+    Moore,
+    /// The diamond of cells at most `R` steps away
+    VonNeumann,
+}
+
+impl Neighborhood {
+    /// Offsets of the cells within `radius` of a cell, without the cell itself
+    pub fn offsets(&self, radius: usize) -> Vec<(isize, isize)> {
+        let r = radius as isize;
+        (-r..=r)
+            .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+            .filter(|&(dx, dy)| match self {
+                Neighborhood::Moore => true,
+                Neighborhood::VonNeumann => dx.abs() + dy.abs() <= r,
+            })
+            .collect()
+    }
+}
+
+/// How many of the cells at `offsets` around `(x, y)` satisfy `f`, wrapping around
+/// the edges
+pub(crate) fn count_around<C: Copy>(
+    cells: &DoubleVec<C>,
+    offsets: &[(isize, isize)],
+    (x, y): Index,
+    f: impl Fn(C) -> bool,
+) -> usize {
+    let (w, h) = (cells[0].len() as isize, cells.len() as isize);
+    offsets
+        .iter()
+        .filter(|(dx, dy)| {
+            let i = (x as isize + dx).rem_euclid(w) as usize;
+            let j = (y as isize + dy).rem_euclid(h) as usize;
+            f(cells[j][i])
+        })
+        .count()
+}
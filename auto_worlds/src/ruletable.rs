@@ -0,0 +1,792 @@
+use std::{cell::RefCell, collections::HashMap, str::FromStr};
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+pub use crate::neighborhood::Neighborhood;
+use crate::PROPORTION;
+
+/// Golly's WireWorld, with 0 empty, 1 electron head, 2 electron tail and 3 conductor.
+/// Used when no rule file is given
+pub const WIREWORLD: &str = "\
+@RULE WireWorld
+
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+
+var a={0,1,2,3}
+var b={0,1,2,3}
+var c={0,1,2,3}
+var d={0,1,2,3}
+var e={0,1,2,3}
+var f={0,1,2,3}
+var g={0,1,2,3}
+var h={0,1,2,3}
+var i={0,2,3}
+var j={0,2,3}
+var k={0,2,3}
+var l={0,2,3}
+var m={0,2,3}
+var n={0,2,3}
+var o={0,2,3}
+
+# electron heads become tails and tails become conductor
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+# conductor next to one or two heads becomes a head
+3,1,i,j,k,l,m,n,o,1
+3,1,1,i,j,k,l,m,n,1
+
+@COLORS
+0 48 48 48
+1 0 128 255
+2 255 255 255
+3 255 128 0
+";
+
+pub type Rgb = [u8; 3];
+
+/// A cell of a table-driven world, which knows the color its rule gives its state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    state: u8,
+    states: u8,
+    color: Rgb,
+}
+
+impl Cell {
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    pub fn color(&self) -> Rgb {
+        self.color
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            state: 0,
+            states: 2,
+            color: [0, 0, 0],
+        }
+    }
+}
+
+impl CellLike for Cell {
+    /// The next state, with the color the world gives it once the cell is set
+    fn next_state(&self) -> Self {
+        Self {
+            state: (self.state + 1) % self.states,
+            ..*self
+        }
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            state: !rng.gen_bool(PROPORTION) as u8,
+            ..Self::default()
+        }
+    }
+}
+
+/// A state, or a variable standing for any state out of a set
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Term {
+    State(u8),
+    Var(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Transition {
+    center: Term,
+    neighbors: Vec<Term>,
+    output: Term,
+}
+
+/// The orders, out of the written one, in which neighbors may be matched
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Symmetry {
+    Orders(Vec<Vec<usize>>),
+    /// Any order at all
+    Permute,
+}
+
+impl Symmetry {
+    /// Neighbors are counted clockwise from north, for `count` of them
+    fn new(name: &str, count: usize) -> Result<Self, String> {
+        let (step, reflect) = match (name, count) {
+            ("none", _) => (count, false),
+            ("permute", _) => return Ok(Self::Permute),
+            ("reflect_horizontal", _) => (count, true),
+            ("rotate4", 4) | ("rotate8", 8) => (1, false),
+            ("rotate4reflect", 4) | ("rotate8reflect", 8) => (1, true),
+            ("rotate4", 8) => (2, false),
+            ("rotate4reflect", 8) => (2, true),
+            _ => return Err(format!("`{}` symmetry is not supported here", name)),
+        };
+
+        let mut orders = vec![];
+        for shift in (0..count).step_by(step) {
+            let rotated: Vec<usize> = (0..count).map(|k| (k + shift) % count).collect();
+            if reflect {
+                // north stays put and east swaps with west
+                orders.push(rotated.iter().map(|&k| (count - k) % count).collect());
+            }
+            orders.push(rotated);
+        }
+        Ok(Self::Orders(orders))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Table {
+    vars: Vec<Vec<u8>>,
+    symmetry: Symmetry,
+    transitions: Vec<Transition>,
+}
+
+impl Table {
+    fn parse(lines: &[&str]) -> Result<(u8, Neighborhood, Self), String> {
+        let mut states = None;
+        let mut neighborhood = None;
+        let mut symmetry = String::from("none");
+        let mut names: Vec<String> = vec![];
+        let mut vars: Vec<Vec<u8>> = vec![];
+        let mut transitions = vec![];
+
+        for line in lines {
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim();
+                match key.trim() {
+                    "n_states" => {
+                        let n: u8 = value
+                            .parse()
+                            .map_err(|_| format!("`{}` is not a number of states", value))?;
+                        if n < 2 {
+                            return Err(String::from("a rule needs at least 2 states"));
+                        }
+                        states = Some(n)
+                    }
+                    "neighborhood" => {
+                        neighborhood = Some(match &value.to_ascii_lowercase()[..] {
+                            "moore" => Neighborhood::Moore,
+                            "vonneumann" => Neighborhood::VonNeumann,
+                            _ => {
+                                return Err(format!("`{}` neighborhoods are not supported", value))
+                            }
+                        })
+                    }
+                    "symmetries" => symmetry = value.to_string(),
+                    _ => return Err(format!("unknown setting `{}`", key)),
+                }
+                continue;
+            }
+
+            let states = states.ok_or("`n_states` should come before the transitions")?;
+            let neighborhood = neighborhood.ok_or("`neighborhood` should come first")?;
+            let term = |token: &str| -> Result<Term, String> {
+                match token.parse::<u8>() {
+                    Ok(state) if state < states => Ok(Term::State(state)),
+                    Ok(state) => Err(format!("state {} is out of range", state)),
+                    Err(_) => names
+                        .iter()
+                        .position(|name| name == token)
+                        .map(Term::Var)
+                        .ok_or_else(|| format!("unknown variable `{}`", token)),
+                }
+            };
+
+            if let Some(var) = line.strip_prefix("var ") {
+                let (name, values) = var
+                    .split_once('=')
+                    .ok_or_else(|| format!("`{}` is missing a `=`", line))?;
+                let mut set = vec![];
+                for token in tokens(values.trim().trim_start_matches('{').trim_end_matches('}')) {
+                    match term(token)? {
+                        Term::State(state) => set.push(state),
+                        Term::Var(other) => set.extend(&vars[other]),
+                    }
+                }
+                set.sort_unstable();
+                set.dedup();
+                names.push(name.trim().to_string());
+                vars.push(set);
+                continue;
+            }
+
+            let count = match neighborhood {
+                Neighborhood::Moore => 8,
+                Neighborhood::VonNeumann => 4,
+            };
+            let mut terms = tokens(line).collect::<Vec<_>>();
+            // with up to 10 states transitions may be written without separators
+            if terms.len() == 1 && states <= 10 && line.len() == count + 2 {
+                terms = (0..line.len()).map(|i| &line[i..i + 1]).collect();
+            }
+            if terms.len() != count + 2 {
+                return Err(format!("`{}` should have {} entries", line, count + 2));
+            }
+            let terms = terms.into_iter().map(term).collect::<Result<Vec<_>, _>>()?;
+            transitions.push(Transition {
+                center: terms[0],
+                neighbors: terms[1..=count].to_vec(),
+                output: terms[count + 1],
+            });
+        }
+
+        let states = states.ok_or("missing `n_states`")?;
+        let neighborhood = neighborhood.ok_or("missing `neighborhood`")?;
+        let count = match neighborhood {
+            Neighborhood::Moore => 8,
+            Neighborhood::VonNeumann => 4,
+        };
+        let table = Self {
+            vars,
+            symmetry: Symmetry::new(&symmetry, count)?,
+            transitions,
+        };
+        Ok((states, neighborhood, table))
+    }
+
+    /// The output of the first transition matching, if any does
+    fn next(&self, center: u8, neighbors: &[u8]) -> Option<u8> {
+        self.transitions.iter().find_map(|t| {
+            let mut bound = vec![None; self.vars.len()];
+            if !self.bind(t.center, center, &mut bound) {
+                return None;
+            }
+            let bound = match &self.symmetry {
+                Symmetry::Orders(orders) => orders.iter().find_map(|order| {
+                    let mut bound = bound.clone();
+                    t.neighbors
+                        .iter()
+                        .zip(order)
+                        .all(|(&term, &k)| self.bind(term, neighbors[k], &mut bound))
+                        .then_some(bound)
+                }),
+                Symmetry::Permute => {
+                    let mut used = vec![false; neighbors.len()];
+                    self.permuted(&t.neighbors, neighbors, &mut used, bound)
+                }
+            }?;
+            match t.output {
+                Term::State(state) => Some(state),
+                Term::Var(var) => bound[var],
+            }
+        })
+    }
+
+    /// Matches `term` against `state`, binding variables the first time they are seen
+    fn bind(&self, term: Term, state: u8, bound: &mut [Option<u8>]) -> bool {
+        match term {
+            Term::State(s) => s == state,
+            Term::Var(var) => match bound[var] {
+                Some(value) => value == state,
+                None if self.vars[var].contains(&state) => {
+                    bound[var] = Some(state);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    /// Matches every term to a different neighbor, in any order
+    fn permuted(
+        &self,
+        terms: &[Term],
+        neighbors: &[u8],
+        used: &mut [bool],
+        bound: Vec<Option<u8>>,
+    ) -> Option<Vec<Option<u8>>> {
+        let Some((&term, rest)) = terms.split_first() else {
+            return Some(bound);
+        };
+        for k in 0..neighbors.len() {
+            if used[k] {
+                continue;
+            }
+            let mut attempt = bound.clone();
+            if self.bind(term, neighbors[k], &mut attempt) {
+                used[k] = true;
+                let found = self.permuted(rest, neighbors, used, attempt);
+                used[k] = false;
+                if found.is_some() {
+                    return found;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Splits on commas and whitespace
+fn tokens(s: &str) -> impl Iterator<Item = &str> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+}
+
+/// A decision tree with one level per input, the last of which holds the new states
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Tree {
+    nodes: Vec<Vec<usize>>,
+}
+
+impl Tree {
+    fn parse(lines: &[&str]) -> Result<(u8, Neighborhood, Self), String> {
+        let mut settings = HashMap::new();
+        let mut nodes: Vec<(usize, Vec<usize>)> = vec![];
+        for line in lines {
+            if let Some((key, value)) = line.split_once('=') {
+                let value: usize = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a number", value))?;
+                settings.insert(key.trim(), value);
+                continue;
+            }
+            let numbers = tokens(line)
+                .map(|n| n.parse().map_err(|_| format!("`{}` is not a number", n)))
+                .collect::<Result<Vec<usize>, String>>()?;
+            let (&level, children) = numbers
+                .split_first()
+                .ok_or_else(|| format!("`{}` is not a node", line))?;
+            nodes.push((level, children.to_vec()));
+        }
+
+        let setting = |key: &str| {
+            settings
+                .get(key)
+                .copied()
+                .ok_or_else(|| format!("missing `{}`", key))
+        };
+        let states = setting("num_states")?;
+        if !(2..=255).contains(&states) {
+            return Err(format!("{} is not a supported number of states", states));
+        }
+        let neighborhood = match setting("num_neighbors")? {
+            8 => Neighborhood::Moore,
+            4 => Neighborhood::VonNeumann,
+            n => return Err(format!("{} neighbors are not supported", n)),
+        };
+        if nodes.len() != setting("num_nodes")? || nodes.is_empty() {
+            return Err(String::from(
+                "the number of nodes does not match `num_nodes`",
+            ));
+        }
+
+        for (i, (level, children)) in nodes.iter().enumerate() {
+            // level 0 would be below the states the leaves give
+            let valid = *level > 0
+                && children.len() == states
+                && children.iter().all(|&child| match level {
+                    1 => child < states,
+                    _ => child < i && level.checked_sub(1) == Some(nodes[child].0),
+                });
+            if !valid {
+                return Err(format!("node {} is not valid", i));
+            }
+        }
+        let inputs = match neighborhood {
+            Neighborhood::Moore => 9,
+            Neighborhood::VonNeumann => 5,
+        };
+        if nodes[nodes.len() - 1].0 != inputs {
+            return Err(String::from("the root should have one level per input"));
+        }
+
+        let tree = Self {
+            nodes: nodes.into_iter().map(|(_, children)| children).collect(),
+        };
+        Ok((states as u8, neighborhood, tree))
+    }
+
+    /// Walks down from the root, taking inputs in the order Golly does: the diagonals
+    /// (northwest, northeast, southwest, southeast), then north, west, east, south and
+    /// finally the center
+    fn next(&self, center: u8, neighbors: &[u8]) -> u8 {
+        let inputs: Vec<u8> = match neighbors.len() {
+            8 => [7, 1, 5, 3, 0, 6, 2, 4]
+                .iter()
+                .map(|&k| neighbors[k])
+                .collect(),
+            _ => [0, 3, 1, 2].iter().map(|&k| neighbors[k]).collect(),
+        };
+        let mut node = self.nodes.len() - 1;
+        for input in inputs.into_iter().chain([center]) {
+            node = self.nodes[node][input as usize];
+        }
+        node as u8
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Logic {
+    Table(Table),
+    Tree(Tree),
+}
+
+/// A rule loaded from a Golly `.rule` file with a `@TABLE` or `@TREE` section, and
+/// colors from its `@COLORS` section
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub name: String,
+    pub states: u8,
+    pub neighborhood: Neighborhood,
+    logic: Logic,
+    colors: Vec<Rgb>,
+}
+
+impl Rule {
+    /// The state of a cell next tick, given its neighbors clockwise starting from north.
+    /// Cells no transition applies to stay as they are
+    pub fn next(&self, center: u8, neighbors: &[u8]) -> u8 {
+        match &self.logic {
+            Logic::Table(table) => table.next(center, neighbors).unwrap_or(center),
+            Logic::Tree(tree) => tree.next(center, neighbors),
+        }
+    }
+
+    pub fn color(&self, state: u8) -> Rgb {
+        self.colors[state as usize]
+    }
+
+    /// A cell in `state`, wrapped around to the states of the rule
+    pub fn cell(&self, state: u8) -> Cell {
+        let state = state % self.states;
+        Cell {
+            state,
+            states: self.states,
+            color: self.color(state),
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        WIREWORLD.parse().unwrap()
+    }
+}
+
+/// Black for state 0 and a gradient from red to yellow for the rest, unless `@COLORS`
+/// says otherwise: `state r g b` sets one state and `r g b r g b` a gradient from the
+/// first color for state 1 to the second for the last state
+fn colors(states: u8, lines: &[&str]) -> Result<Vec<Rgb>, String> {
+    let gradient = |from: Rgb, to: Rgb| {
+        (1..states).map(move |state| {
+            let t = match states {
+                2 => 0.0,
+                _ => (state - 1) as f64 / (states - 2) as f64,
+            };
+            let mix = |i: usize| (from[i] as f64 + (to[i] as f64 - from[i] as f64) * t) as u8;
+            (state, [mix(0), mix(1), mix(2)])
+        })
+    };
+
+    let mut colors = vec![[0, 0, 0]; states as usize];
+    for (state, color) in gradient([255, 0, 0], [255, 255, 0]) {
+        colors[state as usize] = color;
+    }
+    for line in lines {
+        let numbers = tokens(line)
+            .map(|n| n.parse().map_err(|_| format!("`{}` is not a color", n)))
+            .collect::<Result<Vec<u8>, String>>()?;
+        match numbers[..] {
+            [state, r, g, b] if state < states => colors[state as usize] = [r, g, b],
+            [state, ..] if numbers.len() == 4 => {
+                return Err(format!("state {} in @COLORS is out of range", state))
+            }
+            [r1, g1, b1, r2, g2, b2] => {
+                for (state, color) in gradient([r1, g1, b1], [r2, g2, b2]) {
+                    colors[state as usize] = color;
+                }
+            }
+            _ => return Err(format!("`{}` is not a color", line)),
+        }
+    }
+    Ok(colors)
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses the contents of a `.rule` file. Sections other than `@RULE`, `@TABLE`,
+    /// `@TREE` and `@COLORS` are skipped
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = String::new();
+        let mut sections: HashMap<String, Vec<&str>> = HashMap::new();
+        let mut section = String::new();
+        for line in s.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if let Some(header) = line.strip_prefix('@') {
+                let (header, rest) = header.split_once(' ').unwrap_or((header, ""));
+                section = header.trim_end_matches(':').to_ascii_uppercase();
+                if section == "RULE" {
+                    name = rest.trim().to_string();
+                }
+                sections.entry(section.clone()).or_default();
+            } else if !line.is_empty() {
+                sections.entry(section.clone()).or_default().push(line);
+            }
+        }
+
+        let (states, neighborhood, logic) = match (sections.get("TABLE"), sections.get("TREE")) {
+            (Some(lines), _) => {
+                let (states, neighborhood, table) = Table::parse(lines)?;
+                (states, neighborhood, Logic::Table(table))
+            }
+            (None, Some(lines)) => {
+                let (states, neighborhood, tree) = Tree::parse(lines)?;
+                (states, neighborhood, Logic::Tree(tree))
+            }
+            (None, None) => return Err(String::from("the rule has no @TABLE or @TREE")),
+        };
+        let colors = colors(states, sections.get("COLORS").map_or(&[][..], |c| c))?;
+
+        Ok(Self {
+            name,
+            states,
+            neighborhood,
+            logic,
+            colors,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// A world following a Golly rule. Neighborhoods seen before are remembered, since
+/// matching against a table can take a while
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    seen: RefCell<HashMap<[u8; 9], u8>>,
+}
+
+impl World {
+    /// States of the neighbors of `(x, y)`, clockwise from north
+    fn around(&self, (x, y): Index) -> Vec<u8> {
+        let Dimensions(w, h) = self.config.dimensions;
+        let (x, y, w, h) = (x as isize, y as isize, w as isize, h as isize);
+        let offsets: &[(isize, isize)] = match self.config.rule.neighborhood {
+            Neighborhood::Moore => &[
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+            ],
+            Neighborhood::VonNeumann => &[(0, -1), (1, 0), (0, 1), (-1, 0)],
+        };
+        offsets
+            .iter()
+            .map(|(dx, dy)| {
+                let (i, j) = ((x + dx).rem_euclid(w), (y + dy).rem_euclid(h));
+                self.cells[j as usize][i as usize].state
+            })
+            .collect()
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    /// Cells are brought to the states and colors of the rule
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let cells: DoubleVec<Cell> = cells
+            .into_iter()
+            .map(|row| row.into_iter().map(|c| config.rule.cell(c.state)).collect())
+            .collect();
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+            seen: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+        let rule = &self.config().rule;
+        let mut seen = self.seen.borrow_mut();
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                let center = self.cells[j][i].state;
+                let neighbors = self.around(p);
+                let mut key = [0; 9];
+                key[0] = center;
+                key[1..=neighbors.len()].copy_from_slice(&neighbors);
+                let next = *seen
+                    .entry(key)
+                    .or_insert_with(|| rule.next(center, &neighbors));
+                if next != center {
+                    delta.push((p, rule.cell(next)));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn click(&mut self, i @ (x, y): Index) {
+        let cell = self.cells[y][x].next_state();
+        self.set_cells(vec![(i, cell)]);
+    }
+
+    /// Painted cells are brought to the states and colors of the rule
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        let changes: Vec<(Index, Cell)> = changes
+            .into_iter()
+            .map(|(i, c)| (i, self.config.rule.cell(c.state)))
+            .collect();
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::wireworld;
+
+    #[test]
+    fn tables_match_the_native_wireworld() {
+        let dimensions = Dimensions(16, 12);
+        let native = wireworld::World::new_random(
+            &mut rand::thread_rng(),
+            wireworld::WConfig { dimensions },
+        );
+        let rule = Rule::default();
+        assert_eq!(rule.name, "WireWorld");
+        assert_eq!(rule.color(3), [255, 128, 0]);
+
+        let state = |c: &wireworld::Cell| match c {
+            wireworld::Cell::Empty => 0,
+            wireworld::Cell::Head => 1,
+            wireworld::Cell::Tail => 2,
+            wireworld::Cell::Conductor => 3,
+        };
+        let states = |cells: &DoubleVec<Cell>| -> Vec<Vec<u8>> {
+            cells
+                .iter()
+                .map(|row| row.iter().map(Cell::state).collect())
+                .collect()
+        };
+        let cells = native
+            .cells()
+            .iter()
+            .map(|row| row.iter().map(|c| rule.cell(state(c))).collect())
+            .collect();
+
+        let mut native = native;
+        let mut table = World::new(cells, WConfig { dimensions, rule });
+        for _ in 0..10 {
+            native.tick();
+            table.tick();
+            let expected: Vec<Vec<u8>> = native
+                .cells()
+                .iter()
+                .map(|row| row.iter().map(state).collect())
+                .collect();
+            assert_eq!(states(table.cells()), expected);
+        }
+    }
+
+    #[test]
+    fn parses_tables_with_symmetries() {
+        let rule: Rule = "@RULE Spread\n@TABLE\nn_states:3\nneighborhood:vonNeumann\n\
+                          symmetries:rotate4\nvar a={0,2}\n0,1,a,a,a,1\n011002\n"
+            .parse()
+            .unwrap();
+        assert_eq!(rule.neighborhood, Neighborhood::VonNeumann);
+        // one state 1 neighbor with the others equal, in any rotation
+        assert_eq!(rule.next(0, &[0, 0, 1, 0]), 1);
+        assert_eq!(rule.next(0, &[2, 2, 2, 1]), 1);
+        assert_eq!(rule.next(0, &[0, 2, 1, 0]), 0);
+        // written without commas, and rotated
+        assert_eq!(rule.next(0, &[1, 0, 0, 1]), 2);
+        // black and the default red to yellow gradient
+        assert_eq!(rule.color(0), [0, 0, 0]);
+        assert_eq!(rule.color(2), [255, 255, 0]);
+
+        assert!("@TABLE\nn_states:2\nneighborhood:hexagonal\n"
+            .parse::<Rule>()
+            .is_err());
+        assert!("@TABLE\nn_states:2\nneighborhood:Moore\n0,1,0\n"
+            .parse::<Rule>()
+            .is_err());
+    }
+
+    #[test]
+    fn trees_shift_cells_down() {
+        // the new state is the state of the northern neighbor
+        let tree = "@RULE Down\n@TREE\nnum_states=2\nnum_neighbors=4\nnum_nodes=9\n\
+                    1 0 0\n1 1 1\n2 0 0\n2 1 1\n3 2 2\n3 3 3\n4 4 4\n4 5 5\n5 6 7\n\
+                    @COLORS\n1 0 255 0\n";
+        let rule: Rule = tree.parse().unwrap();
+        assert_eq!(rule.color(1), [0, 255, 0]);
+
+        let dimensions = Dimensions(3, 4);
+        let mut cells = vec![vec![Cell::default(); 3]; 4];
+        cells[0][1] = rule.cell(1);
+        let mut world = World::new(cells, WConfig { dimensions, rule });
+        world.tick();
+        world.tick();
+        assert_eq!(world.cells()[2][1].state(), 1);
+        assert_eq!(world.delta().len(), 2);
+
+        // a node of level 0 is an error rather than an underflow
+        let level_zero =
+            "@RULE Zero\n@TREE\nnum_states=2\nnum_neighbors=4\nnum_nodes=2\n1 0 0\n0 0 0\n";
+        assert_eq!(
+            level_zero.parse::<Rule>().err(),
+            Some(String::from("node 1 is not valid"))
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{diff, linearize, Dimensions, DoubleVec, History, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Grains a cell holds before it topples
+pub const CAPACITY: u32 = 4;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub grains: u32,
+}
+
+impl Cell {
+    pub fn new(grains: u32) -> Self {
+        Self { grains }
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.grains < CAPACITY
+    }
+}
+
+impl CellLike for Cell {
+    /// One more grain
+    fn next_state(&self) -> Self {
+        Self::new(self.grains + 1)
+    }
+
+    /// Any stable number of grains
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(rng.gen_range(0..CAPACITY))
+    }
+}
+
+/// Where a grain is dropped whenever the pile is stable
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DropSite {
+    /// Grains only come from clicks
+    Nowhere,
+    #[default]
+    Center,
+    /// A uniformly random cell every time
+    Random,
+}
+
+impl FromStr for DropSite {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "nowhere" | "none" => Ok(Self::Nowhere),
+            "center" => Ok(Self::Center),
+            "random" => Ok(Self::Random),
+            _ => Err(format!("unknown drop `{}`", s)),
+        }
+    }
+}
+
+/// How much of an avalanche happens in a tick
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Relax {
+    /// Every unstable cell topples once
+    #[default]
+    Step,
+    /// Cells topple until the pile is stable again
+    Full,
+}
+
+impl FromStr for Relax {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "step" => Ok(Self::Step),
+            "full" => Ok(Self::Full),
+            _ => Err(format!("unknown relaxation `{}`", s)),
+        }
+    }
+}
+
+/// What it took for the pile to become stable again
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Avalanche {
+    /// How many times a cell toppled
+    pub size: usize,
+    /// In how many waves of simultaneous topplings
+    pub duration: usize,
+}
+
+/// Every unstable cell topples once, giving a grain to each of its 4 neighbors. Grains
+/// given past the edges are lost. Returns how many cells toppled
+pub fn topple(cells: &mut DoubleVec<Cell>) -> usize {
+    let (w, h) = (cells[0].len(), cells.len());
+    let unstable: Vec<Index> = linearize(cells.clone())
+        .into_iter()
+        .filter(|(_, c)| !c.is_stable())
+        .map(|(i, _)| i)
+        .collect();
+    for &(x, y) in unstable.iter() {
+        cells[y][x].grains -= CAPACITY;
+        let around = [
+            (x.checked_sub(1), Some(y)),
+            (Some(x + 1).filter(|&i| i < w), Some(y)),
+            (Some(x), y.checked_sub(1)),
+            (Some(x), Some(y + 1).filter(|&j| j < h)),
+        ];
+        for (i, j) in around {
+            if let (Some(i), Some(j)) = (i, j) {
+                cells[j][i].grains += 1;
+            }
+        }
+    }
+    unstable.len()
+}
+
+/// Topples until the pile is stable
+pub fn relax(cells: &mut DoubleVec<Cell>) -> Avalanche {
+    let mut avalanche = Avalanche::default();
+    loop {
+        match topple(cells) {
+            0 => break avalanche,
+            toppled => {
+                avalanche.size += toppled;
+                avalanche.duration += 1;
+            }
+        }
+    }
+}
+
+/// The identity of the sandpile group of a grid: the stable pile that, added to any
+/// recurrent pile and relaxed, gives that pile back. It is twice the fullest stable pile
+/// minus that relaxed, relaxed
+pub fn identity(Dimensions(w, h): Dimensions) -> DoubleVec<Cell> {
+    let twice = 2 * (CAPACITY - 1);
+    let mut full = vec![vec![Cell::new(twice); w]; h];
+    relax(&mut full);
+    let mut identity = full
+        .iter()
+        .map(|row| row.iter().map(|c| Cell::new(twice - c.grains)).collect())
+        .collect();
+    relax(&mut identity);
+    identity
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub drop: DropSite,
+    pub relax: Relax,
+    /// Seed of the random numbers picking where [DropSite::Random] drops
+    pub seed: u64,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// The abelian sandpile: cells with 4 grains or more topple onto their neighbors, and
+/// grains fall off the edges. A grain is dropped whenever the pile is stable, and the
+/// avalanches that follow are recorded
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    rng: StdRng,
+    /// The avalanche going on, when relaxing by [Relax::Step]
+    avalanche: Option<Avalanche>,
+    avalanches: History<Avalanche>,
+}
+
+impl World {
+    /// The avalanches that came to an end, oldest first, as far back as the [History] goes
+    pub fn avalanches(&self) -> &History<Avalanche> {
+        &self.avalanches
+    }
+
+    /// How many avalanches there were of every size, whose log-log plot is a power law
+    pub fn size_counts(&self) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for avalanche in self.avalanches.iter() {
+            *counts.entry(avalanche.size).or_default() += 1;
+        }
+        counts
+    }
+
+    /// The [World::size_counts] as comma separated values, for plotting on log-log axes
+    pub fn sizes_csv(&self) -> String {
+        let mut csv = String::from("size,count\n");
+        for (size, count) in self.size_counts() {
+            csv += &format!("{},{}\n", size, count);
+        }
+        csv
+    }
+
+    /// The grid after the upcoming tick, with the avalanche still going on after it and
+    /// the one it ended
+    fn step(&self, rng: &mut StdRng) -> (DoubleVec<Cell>, Option<Avalanche>, Option<Avalanche>) {
+        let Dimensions(w, h) = self.config.dimensions;
+        let mut cells = self.cells.clone();
+        let stable = |cells: &DoubleVec<Cell>| cells.iter().flatten().all(Cell::is_stable);
+
+        let mut avalanche = match self.avalanche {
+            Some(avalanche) => avalanche,
+            None => {
+                if stable(&cells) {
+                    let spot = match self.config.drop {
+                        DropSite::Nowhere => None,
+                        DropSite::Center => Some((w / 2, h / 2)),
+                        DropSite::Random => Some((rng.gen_range(0..w), rng.gen_range(0..h))),
+                    };
+                    if let Some((x, y)) = spot {
+                        cells[y][x].grains += 1;
+                    }
+                }
+                if stable(&cells) {
+                    return (cells, None, None);
+                }
+                Avalanche::default()
+            }
+        };
+
+        match self.config.relax {
+            Relax::Step => {
+                avalanche.size += topple(&mut cells);
+                avalanche.duration += 1;
+            }
+            Relax::Full => {
+                let rest = relax(&mut cells);
+                avalanche.size += rest.size;
+                avalanche.duration += rest.duration;
+            }
+        }
+        if stable(&cells) {
+            (cells, None, Some(avalanche))
+        } else {
+            (cells, Some(avalanche), None)
+        }
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        Self {
+            cells,
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            delta: linearize(clone),
+            avalanche: None,
+            avalanches: History::new(),
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let (cells, _, _) = self.step(&mut self.rng.clone());
+        diff(&self.cells, &cells)
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let mut rng = self.rng.clone();
+        let (cells, avalanche, ended) = self.step(&mut rng);
+        self.delta = diff(&self.cells, &cells);
+        self.cells = cells;
+        self.rng = rng;
+        self.avalanche = avalanche;
+        self.avalanches.extend(ended);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(relax: Relax) -> WConfig {
+        WConfig {
+            dimensions: Dimensions(7, 7),
+            drop: DropSite::Random,
+            relax,
+            seed: 5,
+        }
+    }
+
+    #[test]
+    fn relaxing_at_once_or_in_steps_sees_the_same_avalanches() {
+        let mut full = World::new_blank(config(Relax::Full));
+        let mut step = World::new_blank(config(Relax::Step));
+        for _ in 0..300 {
+            full.tick();
+            assert!(full.cells().iter().flatten().all(Cell::is_stable));
+        }
+        while step.avalanches().len() < full.avalanches().len() {
+            step.tick();
+        }
+        assert!(!full.avalanches().is_empty());
+        assert_eq!(step.avalanches(), full.avalanches());
+        assert_eq!(
+            full.size_counts().values().sum::<usize>(),
+            full.avalanches().len()
+        );
+        let csv = full.sizes_csv();
+        assert!(csv.starts_with("size,count\n"));
+        assert_eq!(csv.lines().count(), full.size_counts().len() + 1);
+
+        // a single cell loses all 4 grains at once
+        let mut cells = vec![vec![Cell::new(3)]];
+        let single = WConfig {
+            dimensions: Dimensions(1, 1),
+            ..config(Relax::Step)
+        };
+        let changes = World::new(cells.clone(), single).changes();
+        assert_eq!(changes, vec![((0, 0), Cell::new(0))]);
+        cells[0][0].grains += 1;
+        assert_eq!(
+            relax(&mut cells),
+            Avalanche {
+                size: 1,
+                duration: 1
+            }
+        );
+    }
+
+    #[test]
+    fn the_identity_leaves_piles_alone() {
+        let dimensions = Dimensions(6, 5);
+        let identity = identity(dimensions);
+        assert!(identity.iter().flatten().all(Cell::is_stable));
+
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..5 {
+            let pile: DoubleVec<Cell> = (0..5)
+                .map(|_| (0..6).map(|_| Cell::random(&mut rng)).collect())
+                .collect();
+            // only recurrent piles form the group, and relaxing anything with the
+            // identity added makes it recurrent
+            let mut recurrent = pile.clone();
+            add(&mut recurrent, &identity);
+            relax(&mut recurrent);
+            let mut sum = recurrent.clone();
+            add(&mut sum, &identity);
+            relax(&mut sum);
+            assert!(sum == recurrent);
+        }
+    }
+
+    fn add(cells: &mut DoubleVec<Cell>, other: &DoubleVec<Cell>) {
+        for (row, other) in cells.iter_mut().zip(other) {
+            for (cell, other) in row.iter_mut().zip(other) {
+                cell.grains += other.grains;
+            }
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use auto_cellular::{
+    cell::{CellLike, FiniteCellLike},
+    common::{diff, linearize, Dimensions, DoubleVec, History, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::neighborhood::{count_around, Neighborhood};
+
+/// How many groups agents can belong to
+pub const MAX_GROUPS: u8 = 4;
+
+/// A home, vacant or lived in by an agent of one of the groups
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    Vacant,
+    /// The group, below [MAX_GROUPS]
+    Agent(u8),
+}
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        match self {
+            Cell::Vacant => Cell::Agent(0),
+            Cell::Agent(group) if group + 1 < MAX_GROUPS => Cell::Agent(group + 1),
+            Cell::Agent(_) => Cell::Vacant,
+        }
+    }
+
+    /// Two groups and a tenth of the homes vacant
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        match rng.gen_range(0..10) {
+            0 => Cell::Vacant,
+            n => Cell::Agent(n % 2),
+        }
+    }
+}
+
+impl FiniteCellLike for Cell {
+    const STATE_COUNT: usize = MAX_GROUPS as usize + 1;
+
+    fn to_index(&self) -> usize {
+        match self {
+            Cell::Vacant => 0,
+            Cell::Agent(group) => *group as usize + 1,
+        }
+    }
+
+    fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Cell::Vacant),
+            i if i < Self::STATE_COUNT => Some(Cell::Agent(i as u8 - 1)),
+            _ => None,
+        }
+    }
+
+    fn state_name(&self) -> &'static str {
+        ["Vacant", "Group 1", "Group 2", "Group 3", "Group 4"][self.to_index()]
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    /// How many groups a random world is split into, from 1 to [MAX_GROUPS]
+    pub groups: u8,
+    /// Share of the homes of a random world left vacant
+    pub vacancy: f64,
+    /// Least share of its neighbors an agent wants from its own group before it moves
+    pub threshold: f64,
+    pub neighborhood: Neighborhood,
+    /// Seed of the random numbers deciding who moves first and where to
+    pub seed: u64,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// How segregated the agents are
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Segregation {
+    /// Mean share of an agent's neighbors from its own group, among agents with any
+    pub similarity: f64,
+    /// Share of the agents that want to move
+    pub unhappy: f64,
+}
+
+/// Whether the agent at `at` has enough neighbors of its own group, which it has when it
+/// has no neighbors at all
+fn is_content(
+    cells: &DoubleVec<Cell>,
+    offsets: &[(isize, isize)],
+    at: Index,
+    threshold: f64,
+) -> bool {
+    let cell = cells[at.1][at.0];
+    let alike = count_around(cells, offsets, at, |c| c == cell) as f64;
+    let occupied = count_around(cells, offsets, at, |c| c != Cell::Vacant) as f64;
+    occupied == 0.0 || alike >= threshold * occupied
+}
+
+/// How segregated the agents of `cells` are, see [Segregation]
+pub fn segregation(
+    cells: &DoubleVec<Cell>,
+    offsets: &[(isize, isize)],
+    threshold: f64,
+) -> Segregation {
+    let (mut similarity, mut neighbored, mut unhappy, mut agents) = (0.0, 0, 0, 0);
+    for (at, cell) in linearize(cells.clone()) {
+        if cell == Cell::Vacant {
+            continue;
+        }
+        agents += 1;
+        let occupied = count_around(cells, offsets, at, |c| c != Cell::Vacant);
+        if occupied > 0 {
+            neighbored += 1;
+            similarity += count_around(cells, offsets, at, |c| c == cell) as f64 / occupied as f64;
+        }
+        if !is_content(cells, offsets, at, threshold) {
+            unhappy += 1;
+        }
+    }
+    Segregation {
+        similarity: similarity / neighbored.max(1) as f64,
+        unhappy: unhappy as f64 / agents.max(1) as f64,
+    }
+}
+
+/// Schelling's segregation model: every tick, the agents with too few neighbors of their
+/// own group move to a random vacant home, one after another in a random order
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    offsets: Vec<(isize, isize)>,
+    rng: StdRng,
+    indices: History<Segregation>,
+}
+
+impl World {
+    /// How segregated the agents were at creation and after every tick, as far back as the
+    /// [History] goes
+    pub fn segregation(&self) -> &History<Segregation> {
+        &self.indices
+    }
+
+    fn measure(&self) -> Segregation {
+        segregation(&self.cells, &self.offsets, self.config.threshold)
+    }
+
+    /// The grid after every unhappy agent moved, drawing from `rng`
+    fn step(&self, rng: &mut StdRng) -> DoubleVec<Cell> {
+        let mut cells = self.cells.clone();
+        let (mut vacant, mut unhappy): (Vec<Index>, Vec<Index>) = (vec![], vec![]);
+        for (at, cell) in linearize(self.cells.clone()) {
+            if cell == Cell::Vacant {
+                vacant.push(at);
+            } else if !is_content(&self.cells, &self.offsets, at, self.config.threshold) {
+                unhappy.push(at);
+            }
+        }
+        unhappy.shuffle(rng);
+
+        for (x, y) in unhappy {
+            if vacant.is_empty() {
+                break;
+            }
+            let (i, j) = vacant.swap_remove(rng.gen_range(0..vacant.len()));
+            cells[j][i] = cells[y][x];
+            cells[y][x] = Cell::Vacant;
+            vacant.push((x, y));
+        }
+        cells
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        let mut world = Self {
+            cells,
+            offsets: config.neighborhood.offsets(1),
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            delta: linearize(clone),
+            indices: History::new(),
+        };
+        world.indices.push(world.measure());
+        world
+    }
+
+    /// Homes left vacant with the configured chance, and agents spread evenly among the
+    /// groups otherwise
+    fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        let Dimensions(w, h) = config.dimensions;
+        let groups = config.groups.clamp(1, MAX_GROUPS);
+        let cells = (0..h)
+            .map(|_| {
+                (0..w)
+                    .map(|_| {
+                        if rng.gen_bool(config.vacancy) {
+                            Cell::Vacant
+                        } else {
+                            Cell::Agent(rng.gen_range(0..groups))
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self::new(cells, config)
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    /// What the upcoming tick does, which draws the same numbers the tick will
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        diff(&self.cells, &self.step(&mut self.rng.clone()))
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let mut rng = self.rng.clone();
+        let cells = self.step(&mut rng);
+        self.delta = diff(&self.cells, &cells);
+        self.cells = cells;
+        self.rng = rng;
+        let indices = self.measure();
+        self.indices.push(indices);
+    }
+
+    /// Moving agents by hand is measured again as part of the last tick
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        let indices = self.measure();
+        self.indices.revise(indices);
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use auto_cellular::cell::histogram;
+
+    use super::*;
+
+    fn config(threshold: f64) -> WConfig {
+        WConfig {
+            dimensions: Dimensions(20, 20),
+            groups: 2,
+            vacancy: 0.1,
+            threshold,
+            neighborhood: Neighborhood::Moore,
+            seed: 5,
+        }
+    }
+
+    #[test]
+    fn measures_segregation() {
+        // two halves, where every agent borders the other group on one side
+        let cells: DoubleVec<Cell> = (0..4)
+            .map(|_| (0..4).map(|x| Cell::Agent((x / 2) as u8)).collect())
+            .collect();
+        let offsets = Neighborhood::VonNeumann.offsets(1);
+        let indices = segregation(&cells, &offsets, 0.8);
+        assert_eq!(indices.similarity, 0.75);
+        assert_eq!(indices.unhappy, 1.0);
+        assert_eq!(segregation(&cells, &offsets, 0.75).unhappy, 0.0);
+    }
+
+    #[test]
+    fn unhappy_agents_move_until_segregated() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut world = World::new_random(&mut rng, config(0.5));
+        let agents = histogram(world.cells());
+        for _ in 0..30 {
+            world.tick();
+        }
+        assert_eq!(histogram(world.cells()), agents);
+        let indices = world.segregation();
+        assert_eq!(indices.len(), 31);
+        assert!(indices[30].similarity > indices[0].similarity + 0.2);
+        assert!(indices[30].unhappy < indices[0].unhappy);
+
+        // nobody minds anyone
+        let mut content = World::new_random(&mut rng, config(0.0));
+        assert!(content.changes().is_empty());
+        content.tick();
+        assert_eq!(content.segregation()[1].unhappy, 0.0);
+    }
+}
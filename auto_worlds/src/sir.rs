@@ -0,0 +1,227 @@
+use auto_cellular::{
+    cell::{CellLike, Census},
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::neighborhood::{count_around, Neighborhood};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    Susceptible,
+    Infected,
+    Recovered,
+}
+
+auto_cellular::finite_cell!(Cell {
+    Susceptible => "Susceptible",
+    Infected => "Infected",
+    Recovered => "Recovered",
+});
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        match self {
+            Cell::Susceptible => Cell::Infected,
+            Cell::Infected => Cell::Recovered,
+            Cell::Recovered => Cell::Susceptible,
+        }
+    }
+
+    /// A few infected cells among susceptible ones
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        if rng.gen_bool(0.01) {
+            Cell::Infected
+        } else {
+            Cell::Susceptible
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    /// Chance that a susceptible cell catches the infection from each infected neighbor
+    /// every tick
+    pub infection: f64,
+    /// Chance that an infected cell recovers every tick
+    pub recovery: f64,
+    /// Chance that a recovered cell becomes susceptible again every tick, which is 0 for
+    /// lasting immunity
+    pub immunity_loss: f64,
+    pub neighborhood: Neighborhood,
+    /// Seed of the random numbers the probabilities are drawn against
+    pub seed: u64,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// An SIR(S) epidemic on a lattice, where the infection only spreads between neighbors
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    offsets: Vec<(isize, isize)>,
+    rng: StdRng,
+    census: Census,
+}
+
+impl World {
+    /// How many cells were susceptible, infected and recovered over the last ticks, see
+    /// [Census]
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    fn step(&self, rng: &mut StdRng) -> Vec<(Index, Cell)> {
+        let WConfig {
+            infection,
+            recovery,
+            immunity_loss,
+            ..
+        } = self.config;
+        let mut delta = vec![];
+        for (j, row) in self.cells.iter().enumerate() {
+            for (i, &cell) in row.iter().enumerate() {
+                let next = match cell {
+                    Cell::Susceptible => {
+                        let infected = count_around(&self.cells, &self.offsets, (i, j), |c| {
+                            c == Cell::Infected
+                        });
+                        // escaping every infected neighbor
+                        let escape = (1.0 - infection).powi(infected as i32);
+                        if infected > 0 && rng.gen_bool(1.0 - escape) {
+                            Cell::Infected
+                        } else {
+                            cell
+                        }
+                    }
+                    Cell::Infected if rng.gen_bool(recovery) => Cell::Recovered,
+                    Cell::Recovered if rng.gen_bool(immunity_loss) => Cell::Susceptible,
+                    _ => cell,
+                };
+                if next != cell {
+                    delta.push(((i, j), next));
+                }
+            }
+        }
+        delta
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        let mut census = Census::new();
+        census.record(&cells);
+        Self {
+            cells,
+            offsets: config.neighborhood.offsets(1),
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            delta: linearize(clone),
+            census,
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    /// What the upcoming tick does, which draws the same numbers the tick will
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        self.step(&mut self.rng.clone())
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let mut rng = self.rng.clone();
+        let delta = self.step(&mut rng);
+        for &((x, y), cell) in delta.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.rng = rng;
+        self.census.record(&self.cells);
+        self.delta = delta;
+    }
+
+    /// Painted cells are counted in the census of the last tick
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.census.revise(&self.cells);
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(infection: f64, recovery: f64, immunity_loss: f64) -> WConfig {
+        WConfig {
+            dimensions: Dimensions(21, 21),
+            infection,
+            recovery,
+            immunity_loss,
+            neighborhood: Neighborhood::VonNeumann,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn certain_infection_spreads_as_a_diamond() {
+        let mut cells = vec![vec![Cell::Susceptible; 21]; 21];
+        cells[10][10] = Cell::Infected;
+        let mut world = World::new(cells, config(1.0, 0.0, 0.0));
+        for _ in 0..5 {
+            world.tick();
+        }
+        // everyone within t steps
+        let infected = world.census().series(Cell::Infected);
+        assert_eq!(infected, vec![1, 5, 13, 25, 41, 61]);
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_epidemic() {
+        let run = || {
+            let mut rng = StdRng::seed_from_u64(1);
+            let mut world = World::new_random(&mut rng, config(0.3, 0.1, 0.01));
+            for _ in 0..30 {
+                world.tick();
+            }
+            world.census().clone()
+        };
+        let census = run();
+        assert_eq!(census, run());
+        assert_eq!(census.rows().count(), 31);
+        assert!(census
+            .rows()
+            .all(|row| row.iter().sum::<usize>() == 21 * 21));
+    }
+}
@@ -0,0 +1,248 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+pub use crate::lenia::Cell;
+use crate::lenia::{convolve, fields, number, patch, positive, time_step, Kernel};
+
+/// SmoothLife, written like `ra=12;b=0.278,0.365;d=0.267,0.445;an=0.028;am=0.147;dt=1`.
+/// A cell's inner disk is a third as wide as its outer ring
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    /// Outer radius of the ring around a cell, in cells
+    pub radius: f32,
+    /// Fillings of the ring that bring a dead cell to life
+    pub birth: (f32, f32),
+    /// Fillings of the ring that keep an alive cell alive
+    pub survival: (f32, f32),
+    /// How smooth the steps over the filling of the ring are
+    pub alpha_n: f32,
+    /// How smooth the step between dead and alive is
+    pub alpha_m: f32,
+    /// 1 for cells to take their next state at once, less to move towards it over time
+    pub dt: f32,
+}
+
+impl Rule {
+    /// The disk of the cell itself and the ring around it, each weighing 1 altogether
+    pub fn kernels(&self) -> (Kernel, Kernel) {
+        let (outer, inner) = (self.radius, self.radius / 3.0);
+        let r = outer.ceil() as isize;
+        let (mut disk, mut ring): (Kernel, Kernel) = (vec![], vec![]);
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                if distance <= inner {
+                    disk.push(((dx, dy), 1.0));
+                } else if distance <= outer {
+                    ring.push(((dx, dy), 1.0));
+                }
+            }
+        }
+        for kernel in [&mut disk, &mut ring] {
+            let area = kernel.len() as f32;
+            for (_, weight) in kernel.iter_mut() {
+                *weight /= area;
+            }
+        }
+        (disk, ring)
+    }
+
+    /// How alive a cell whose disk is `m` full and whose ring is `n` full becomes
+    pub fn transition(&self, n: f32, m: f32) -> f32 {
+        let sigmoid = |x: f32, a: f32, alpha: f32| 1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp());
+        let between = |x: f32, a: f32, b: f32| {
+            sigmoid(x, a, self.alpha_n) * (1.0 - sigmoid(x, b, self.alpha_n))
+        };
+        let alive = sigmoid(m, 0.5, self.alpha_m);
+        let mix = |dead: f32, living: f32| dead * (1.0 - alive) + living * alive;
+        between(
+            n,
+            mix(self.birth.0, self.survival.0),
+            mix(self.birth.1, self.survival.1),
+        )
+    }
+}
+
+impl Default for Rule {
+    /// The rule of Rafler's paper
+    fn default() -> Self {
+        Self {
+            radius: 12.0,
+            birth: (0.278, 0.365),
+            survival: (0.267, 0.445),
+            alpha_n: 0.028,
+            alpha_m: 0.147,
+            dt: 1.0,
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Missing fields are taken from the [Rule::default]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = fields(s)?;
+        let mut rule = Self::default();
+        let pair = |value: &str| match value.split_once(',') {
+            Some((a, b)) => Ok((number(a)?, number(b)?)),
+            None => Err(format!("`{}` should be two numbers", value)),
+        };
+        for (key, value) in fields.iter() {
+            match &key[..] {
+                "ra" => rule.radius = number(value)?,
+                "b" => rule.birth = pair(value)?,
+                "d" => rule.survival = pair(value)?,
+                "an" => rule.alpha_n = number(value)?,
+                "am" => rule.alpha_m = number(value)?,
+                "dt" => rule.dt = number(value)?,
+                _ => return Err(format!("unknown field `{}`", key)),
+            }
+        }
+        if rule.radius < 3.0 {
+            return Err(format!("the radius of `{}` leaves no inner disk", s));
+        }
+        positive("an", rule.alpha_n)?;
+        positive("am", rule.alpha_m)?;
+        time_step(rule.dt)?;
+        Ok(rule)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ra={};b={},{};d={},{};an={};am={};dt={}",
+            self.radius,
+            self.birth.0,
+            self.birth.1,
+            self.survival.0,
+            self.survival.1,
+            self.alpha_n,
+            self.alpha_m,
+            self.dt
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// SmoothLife: the Game of Life over continuous states and space, where a cell looks at
+/// how full the disk around it and the ring around that are
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    disk: Kernel,
+    ring: Kernel,
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        let (disk, ring) = config.rule.kernels();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+            disk,
+            ring,
+        }
+    }
+
+    /// A random patch in the middle, since a whole random world is too crowded
+    fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        Self::new(patch(rng, config.dimensions), config)
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let rule = &self.config.rule;
+        let mut delta = vec![];
+        for (j, row) in self.cells.iter().enumerate() {
+            for (i, &cell) in row.iter().enumerate() {
+                let m = convolve(&self.cells, &self.disk, (i, j));
+                let n = convolve(&self.cells, &self.ring, (i, j));
+                let target = rule.transition(n, m);
+                let next = Cell::new(cell.value + rule.dt * (target - cell.value));
+                if next != cell {
+                    delta.push(((i, j), next));
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn behaves_like_life_in_the_limits() {
+        let rule: Rule = "ra=6;dt=1".parse().unwrap();
+        assert_eq!(rule.to_string().parse(), Ok(rule.clone()));
+        assert!("ra=2".parse::<Rule>().is_err());
+        for bad in ["an=0", "am=0", "am=NaN", "dt=0", "dt=2"] {
+            assert!(
+                format!("ra=6;{}", bad).parse::<Rule>().is_err(),
+                "{} parsed",
+                bad
+            );
+        }
+
+        // births and survivals where the Game of Life would have them, deaths elsewhere
+        assert!(rule.transition(0.32, 0.0) > 0.9);
+        assert!(rule.transition(0.4, 1.0) > 0.9);
+        assert!(rule.transition(0.4, 0.0) < 0.1);
+        assert!(rule.transition(0.1, 1.0) < 0.1);
+
+        let config = WConfig {
+            dimensions: Dimensions(16, 16),
+            rule,
+        };
+        let mut full = World::new(vec![vec![Cell::new(1.0); 16]; 16], config.clone());
+        full.tick();
+        assert!(full.cells().iter().flatten().all(|c| c.value < 0.1));
+        let mut empty = World::new_blank(config);
+        empty.tick();
+        assert!(empty.cells().iter().flatten().all(|c| c.value < 0.1));
+    }
+}
@@ -0,0 +1,759 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+};
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+use crate::PROPORTION;
+
+/// Well known turmites that can be asked for by name. Strings of `L`, `R`, `N` and `U`
+/// are Langton's ant like turmites with a single state
+pub const PRESETS: [(&str, &str); 6] = [
+    ("langton", "{{{1,2,0},{0,8,0}}}"),
+    ("fibonacci", "{{{1,8,1},{1,8,1}},{{1,2,1},{0,1,0}}}"),
+    ("llrr", "LLRR"),
+    ("square", "LRRRRRLLR"),
+    ("highway", "LLRRRLRLRLLR"),
+    ("triangle", "RRLLLRLLLRRR"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub fn turn(&self, turn: Turn) -> Self {
+        let quarters = match turn {
+            Turn::None => 0,
+            Turn::Right => 1,
+            Turn::UTurn => 2,
+            Turn::Left => 3,
+        };
+        [Self::Up, Self::Right, Self::Down, Self::Left][(*self as usize + quarters) % 4]
+    }
+
+    /// The [Index] one step from `(x, y)` this way, wrapping around the edges
+    pub fn step(&self, (x, y): Index, Dimensions(w, h): Dimensions) -> Index {
+        match self {
+            Self::Up => (x, (y + h - 1) % h),
+            Self::Right => ((x + 1) % w, y),
+            Self::Down => (x, (y + 1) % h),
+            Self::Left => ((x + w - 1) % w, y),
+        }
+    }
+}
+
+/// How a turmite turns, relative to where it is heading
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Turn {
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+impl Turn {
+    /// The number standing for the turn in the compact notation
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::None => 1,
+            Self::Right => 2,
+            Self::UTurn => 4,
+            Self::Left => 8,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(Self::None),
+            2 => Some(Self::Right),
+            4 => Some(Self::UTurn),
+            8 => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter.to_ascii_uppercase() {
+            'N' => Some(Self::None),
+            'R' => Some(Self::Right),
+            'U' => Some(Self::UTurn),
+            'L' => Some(Self::Left),
+            _ => None,
+        }
+    }
+}
+
+/// What a turmite does on a cell: the color to leave behind, how to turn and the
+/// state to go into
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Transition {
+    pub write: u8,
+    pub turn: Turn,
+    pub state: u8,
+}
+
+/// A turmite's transition table, indexed by its state and then the color under it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    table: Vec<Vec<Transition>>,
+}
+
+impl Rule {
+    /// Checks that every state knows every color and that the table only refers to
+    /// states and colors it has
+    pub fn new(table: Vec<Vec<Transition>>) -> Result<Self, String> {
+        let colors = table.first().map(Vec::len).unwrap_or(0);
+        if colors == 0 {
+            return Err(String::from("a turmite needs at least one state and color"));
+        }
+        if table.len() > u8::MAX as usize || colors > u8::MAX as usize {
+            return Err(String::from("a turmite has at most 255 states and colors"));
+        }
+        if table.iter().any(|row| row.len() != colors) {
+            return Err(String::from("every state should have one entry per color"));
+        }
+        if let Some(t) = table
+            .iter()
+            .flatten()
+            .find(|t| t.write as usize >= colors || t.state as usize >= table.len())
+        {
+            return Err(format!(
+                "`{{{},{},{}}}` refers to a color or state the turmite does not have",
+                t.write,
+                t.turn.code(),
+                t.state
+            ));
+        }
+        Ok(Self { table })
+    }
+
+    pub fn states(&self) -> u8 {
+        self.table.len() as u8
+    }
+
+    pub fn colors(&self) -> u8 {
+        self.table[0].len() as u8
+    }
+
+    pub fn transition(&self, state: u8, color: u8) -> Transition {
+        self.table[state as usize][color as usize]
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        PRESETS[0].1.parse().unwrap()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses the compact notation (e.g. `{{{1,2,0},{0,8,0}}}`, where turns are 1 for
+    /// none, 2 for right, 4 for a U-turn and 8 for left), a string of `L`, `R`, `N` and
+    /// `U` giving the turn on each color, and the names in [PRESETS]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((_, rule)) = PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return rule.parse();
+        }
+
+        if !s.starts_with('{') {
+            let turns = s
+                .chars()
+                .map(|c| Turn::from_letter(c).ok_or_else(|| format!("`{}` is not a turn", c)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let colors = turns.len();
+            let row = turns
+                .into_iter()
+                .enumerate()
+                .map(|(color, turn)| Transition {
+                    write: ((color + 1) % colors) as u8,
+                    turn,
+                    state: 0,
+                })
+                .collect();
+            return Self::new(vec![row]);
+        }
+
+        let numbers = s
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<u8>().map_err(|_| format!("`{}` is too big", n)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if numbers.len() % 3 != 0 {
+            return Err(format!("`{}` should be made of triples", s));
+        }
+
+        // the states are the groups found two braces deep
+        let mut depth = 0;
+        let mut sizes = vec![];
+        for c in s.chars() {
+            match c {
+                '{' => {
+                    depth += 1;
+                    if depth == 2 {
+                        sizes.push(0);
+                    } else if depth == 3 {
+                        *sizes.last_mut().ok_or("unbalanced braces")? += 1;
+                    }
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        if depth != 0 || sizes.iter().sum::<usize>() * 3 != numbers.len() {
+            return Err(format!("`{}` has unbalanced braces", s));
+        }
+
+        let mut triples = numbers.chunks(3).map(|t| {
+            Turn::from_code(t[1])
+                .map(|turn| Transition {
+                    write: t[0],
+                    turn,
+                    state: t[2],
+                })
+                .ok_or_else(|| format!("`{}` is not a turn", t[1]))
+        });
+        let table = sizes
+            .into_iter()
+            .map(|size| triples.by_ref().take(size).collect())
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(table)
+    }
+}
+
+impl Display for Rule {
+    /// Writes the compact notation
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let states: Vec<String> = self
+            .table
+            .iter()
+            .map(|row| {
+                let transitions: Vec<String> = row
+                    .iter()
+                    .map(|t| format!("{{{},{},{}}}", t.write, t.turn.code(), t.state))
+                    .collect();
+                format!("{{{}}}", transitions.join(","))
+            })
+            .collect();
+        write!(f, "{{{}}}", states.join(","))
+    }
+}
+
+/// A colored cell. The turmites walking over the cells are kept by the [World]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub color: u8,
+    colors: u8,
+}
+
+impl Cell {
+    pub fn new(color: u8, colors: u8) -> Self {
+        Self {
+            color: color % colors,
+            colors,
+        }
+    }
+
+    /// How many colors the world this cell lives in has
+    pub fn colors(&self) -> u8 {
+        self.colors
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(0, 2)
+    }
+}
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        Self::new(self.color + 1, self.colors)
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(!rng.gen_bool(PROPORTION) as u8, 2)
+    }
+}
+
+/// Where a turmite is, where it is heading, the state it is in and, if it does not
+/// follow the rule of the world, its own rule
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ant {
+    pub position: Index,
+    pub direction: Direction,
+    pub state: u8,
+    pub rule: Option<Rule>,
+}
+
+impl Ant {
+    pub fn new(position: Index, direction: Direction) -> Self {
+        Self {
+            position,
+            direction,
+            state: 0,
+            rule: None,
+        }
+    }
+}
+
+/// What happens when ants try to enter the same cell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Collision {
+    /// They share the cell
+    #[default]
+    Stack,
+    /// The first ant gets in and the others stay where they are
+    Block,
+    /// Like [Collision::Block], but the ants that stay also turn around
+    Bounce,
+    /// None of them make it
+    Annihilate,
+}
+
+impl FromStr for Collision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "stack" => Ok(Self::Stack),
+            "block" => Ok(Self::Block),
+            "bounce" => Ok(Self::Bounce),
+            "annihilate" => Ok(Self::Annihilate),
+            _ => Err(format!("`{}` is not a collision policy", s)),
+        }
+    }
+}
+
+impl Display for Collision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_ascii_lowercase())
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+    pub collision: Collision,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// Turmites walking over a grid of colors, all at once. Unless given some, there is a
+/// single one in the middle, heading up. The ants are not part of the cells, so spaces
+/// draw them from [World::ants] over the cells
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    ants: Vec<Ant>,
+}
+
+impl World {
+    pub fn new_with_ants(cells: DoubleVec<Cell>, config: WConfig, ants: Vec<Ant>) -> Self {
+        let mut world = Self::new(cells, config);
+        world.ants = ants;
+        world.recolor();
+        world
+    }
+
+    /// A random world with `n` ants at random places, heading random ways. They take
+    /// `rules` in turn, or follow the rule of the world if there are none. An empty grid
+    /// has no room for any
+    pub fn random_with_ants<R: rand::Rng + ?Sized>(
+        rng: &mut R,
+        config: WConfig,
+        n: usize,
+        rules: &[Rule],
+    ) -> Self {
+        let Dimensions(w, h) = config.dimensions;
+        let n = if w == 0 || h == 0 { 0 } else { n };
+        let directions = [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ];
+        let ants = (0..n)
+            .map(|i| Ant {
+                rule: rules.get(i % rules.len().max(1)).cloned(),
+                ..Ant::new(
+                    (rng.gen_range(0..w), rng.gen_range(0..h)),
+                    directions[rng.gen_range(0..4)],
+                )
+            })
+            .collect();
+        let world = Self::new_random(rng, config);
+        Self::new_with_ants(world.cells, world.config, ants)
+    }
+
+    pub fn ants(&self) -> &[Ant] {
+        &self.ants
+    }
+
+    /// Puts `ant` in the world, over whatever ants are already there
+    pub fn place_ant(&mut self, ant: Ant) {
+        let (x, y) = ant.position;
+        self.delta = vec![(ant.position, self.cells[y][x])];
+        self.ants.push(ant);
+    }
+
+    /// Takes every ant at `p` out of the world
+    pub fn remove_ants(&mut self, p @ (x, y): Index) {
+        self.ants.retain(|ant| ant.position != p);
+        self.delta = vec![(p, self.cells[y][x])];
+    }
+
+    /// The most colors any of the rules in play use
+    pub fn colors(&self) -> u8 {
+        self.ants
+            .iter()
+            .filter_map(|ant| ant.rule.as_ref())
+            .map(Rule::colors)
+            .fold(self.config.rule.colors(), u8::max)
+    }
+
+    /// Brings every cell to the current colors
+    fn recolor(&mut self) {
+        let colors = self.colors();
+        for cell in self.cells.iter_mut().flatten() {
+            *cell = Cell::new(cell.color, colors);
+        }
+        self.delta = linearize(self.cells.clone());
+    }
+
+    /// Where the ants go when some of them would share a cell, given where they were
+    /// `before` and where they would be after `moved`
+    fn collide(&self, before: &[Ant], mut moved: Vec<Ant>) -> Vec<Ant> {
+        let mut claims: HashMap<Index, Vec<usize>> = HashMap::new();
+        for (i, ant) in moved.iter().enumerate() {
+            claims.entry(ant.position).or_default().push(i);
+        }
+        if self.config.collision == Collision::Annihilate {
+            return moved
+                .into_iter()
+                .filter(|ant| claims[&ant.position].len() == 1)
+                .collect();
+        }
+        if self.config.collision == Collision::Stack {
+            return moved;
+        }
+
+        // ants that stay where they were can block the ants behind them in turn, so
+        // conflicts are settled until there are none left
+        let mut stays = vec![false; moved.len()];
+        let mut conflicts: Vec<Index> = claims
+            .iter()
+            .filter(|(_, ants)| ants.len() > 1)
+            .map(|(&p, _)| p)
+            .collect();
+        while let Some(p) = conflicts.pop() {
+            let ants = claims.remove(&p).unwrap_or_default();
+            // an ant that cannot leave keeps its cell, otherwise the first one gets in
+            let (staying, entering): (Vec<usize>, Vec<usize>) =
+                ants.into_iter().partition(|&i| stays[i]);
+            let mut kept = staying;
+            let mut entering = entering.into_iter();
+            if kept.is_empty() {
+                kept.extend(entering.next());
+            }
+            for i in entering {
+                stays[i] = true;
+                let ant = &mut moved[i];
+                ant.position = before[i].position;
+                if self.config.collision == Collision::Bounce {
+                    ant.direction = ant.direction.turn(Turn::UTurn);
+                }
+                let back = claims.entry(ant.position).or_default();
+                back.push(i);
+                if back.len() > 1 {
+                    conflicts.push(ant.position);
+                }
+            }
+            claims.insert(p, kept);
+        }
+        moved
+    }
+
+    /// Where the ants are next and the cells they change on the way. The cells the ants
+    /// leave and enter are always in the changes, so that they get drawn again
+    fn step(&self) -> (Vec<Ant>, Vec<(Index, Cell)>) {
+        let mut writes: HashMap<Index, u8> = HashMap::new();
+        let mut touched: Vec<Index> = vec![];
+        let moved: Vec<Ant> = self
+            .ants
+            .iter()
+            .map(|ant| {
+                let from @ (x, y) = ant.position;
+                let rule = ant.rule.as_ref().unwrap_or(&self.config.rule);
+                let t = rule.transition(
+                    ant.state % rule.states(),
+                    self.cells[y][x].color % rule.colors(),
+                );
+                // of ants sharing a cell, the last one has the final say
+                writes.insert(from, t.write);
+                touched.push(from);
+                let direction = ant.direction.turn(t.turn);
+                Ant {
+                    position: direction.step(from, self.config.dimensions),
+                    direction,
+                    state: t.state,
+                    ..ant.clone()
+                }
+            })
+            .collect();
+        let ants = self.collide(&self.ants, moved);
+        touched.extend(ants.iter().map(|ant| ant.position));
+
+        let colors = self.colors();
+        let mut seen = HashSet::new();
+        let delta = touched
+            .into_iter()
+            .filter(|&p| seen.insert(p))
+            .map(|p @ (x, y)| {
+                let color = writes.get(&p).copied().unwrap_or(self.cells[y][x].color);
+                (p, Cell::new(color, colors))
+            })
+            .collect();
+        (ants, delta)
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let Dimensions(w, h) = config.dimensions;
+        let ants = if w == 0 || h == 0 {
+            vec![]
+        } else {
+            vec![Ant::new((w / 2, h / 2), Direction::Up)]
+        };
+        let mut world = Self {
+            cells,
+            config,
+            delta: vec![],
+            ants,
+        };
+        world.recolor();
+        world
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        self.step().1
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let (ants, delta) = self.step();
+        for &((x, y), cell) in delta.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.ants = ants;
+        self.delta = delta;
+    }
+
+    /// Cycles through the colors, then places an ant heading up on the first color and
+    /// then takes it away
+    fn click(&mut self, p @ (x, y): Index) {
+        let cell = self.cells[y][x];
+        if self.ants.iter().any(|ant| ant.position == p) {
+            self.remove_ants(p);
+        } else if cell.color + 1 < cell.colors {
+            self.set_cells(vec![(p, cell.next_state())]);
+        } else {
+            self.cells[y][x] = Cell::new(0, cell.colors);
+            self.place_ant(Ant::new(p, Direction::Up));
+        }
+    }
+
+    /// Painted cells take the colors of the world, and leave the ants on them alone
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        let colors = self.colors();
+        let changes: Vec<_> = changes
+            .into_iter()
+            .map(|(p, cell)| (p, Cell::new(cell.color, colors)))
+            .collect();
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use auto_cellular::input::{Button, Input, Modifiers};
+
+    #[test]
+    fn parses_rules() {
+        let langton: Rule = "langton".parse().unwrap();
+        assert_eq!(langton, "RL".parse().unwrap());
+        assert_eq!(langton.to_string(), "{{{1,2,0},{0,8,0}}}");
+        assert_eq!(langton.transition(0, 1).turn, Turn::Left);
+
+        let fibonacci: Rule = "{{{1, 8, 1}, {1, 8, 1}}, {{1, 2, 1}, {0, 1, 0}}}"
+            .parse()
+            .unwrap();
+        assert_eq!((fibonacci.states(), fibonacci.colors()), (2, 2));
+        assert_eq!(fibonacci.to_string(), PRESETS[1].1);
+
+        assert!("{{{1,3,0},{0,8,0}}}".parse::<Rule>().is_err());
+        assert!("{{{1,2,1},{0,8,0}}}".parse::<Rule>().is_err());
+        assert!("{{{1,2,0}},{{0,8,0},{1,2,0}}}".parse::<Rule>().is_err());
+        assert!("RXL".parse::<Rule>().is_err());
+    }
+
+    fn config(collision: Collision) -> WConfig {
+        WConfig {
+            dimensions: Dimensions(5, 5),
+            rule: Rule::default(),
+            collision,
+        }
+    }
+
+    #[test]
+    fn langtons_ant_walks_a_square() {
+        let mut world = World::new_blank(config(Collision::Stack));
+        for _ in 0..4 {
+            world.tick();
+        }
+
+        // four right turns on white leave a black square with the ant back home
+        assert_eq!(world.ants()[0].position, (2, 2));
+        assert_eq!(world.ants()[0].direction, Direction::Up);
+        for (x, y) in [(2, 2), (3, 2), (3, 3), (2, 3)] {
+            assert_eq!(world.cells()[y][x].color, 1);
+        }
+
+        // on black it turns left and flips the cell back
+        world.tick();
+        assert_eq!(world.ants()[0].position, (1, 2));
+        assert_eq!(world.ants()[0].direction, Direction::Left);
+        assert_eq!(world.cells()[2][2], Cell::default());
+        // the cell it entered is drawn again, though its color is the same
+        assert!(world.delta().contains(&((1, 2), Cell::default())));
+    }
+
+    #[test]
+    fn collisions_follow_the_policy() {
+        // both ants turn right into (2, 2)
+        let ants = vec![
+            Ant::new((1, 2), Direction::Up),
+            Ant::new((3, 2), Direction::Down),
+        ];
+        let after = |collision| {
+            let cells = vec![vec![Cell::default(); 5]; 5];
+            let mut world = World::new_with_ants(cells, config(collision), ants.clone());
+            world.tick();
+            assert_eq!(world.cells()[2][1].color, 1);
+            assert_eq!(world.cells()[2][3].color, 1);
+            world
+                .ants()
+                .iter()
+                .map(|ant| (ant.position, ant.direction))
+                .collect::<Vec<_>>()
+        };
+
+        use Direction::*;
+        assert_eq!(after(Collision::Stack), [((2, 2), Right), ((2, 2), Left)]);
+        assert_eq!(after(Collision::Block), [((2, 2), Right), ((3, 2), Left)]);
+        assert_eq!(after(Collision::Bounce), [((2, 2), Right), ((3, 2), Right)]);
+        assert_eq!(after(Collision::Annihilate), []);
+    }
+
+    #[test]
+    fn blocked_ants_block_the_ants_behind_them() {
+        // the first ant heads for where the third one is, which gets blocked by the
+        // second one and so stays where it was
+        let ants = vec![
+            Ant::new((3, 3), Direction::Left),
+            Ant::new((1, 2), Direction::Up),
+            Ant::new((3, 2), Direction::Down),
+        ];
+        for collision in [Collision::Block, Collision::Bounce] {
+            let cells = vec![vec![Cell::default(); 5]; 5];
+            let mut world = World::new_with_ants(cells, config(collision), ants.clone());
+            world.tick();
+            let positions: Vec<Index> = world.ants().iter().map(|ant| ant.position).collect();
+            assert_eq!(positions, [(3, 3), (2, 2), (3, 2)]);
+        }
+    }
+
+    #[test]
+    fn clicks_place_and_remove_ants() {
+        let mut world = World::new_blank(config(Collision::Stack));
+        world.click((0, 0));
+        assert_eq!(world.cells()[0][0].color, 1);
+        world.click((0, 0));
+        assert_eq!(world.cells()[0][0], Cell::default());
+        assert_eq!(world.ants()[1], Ant::new((0, 0), Direction::Up));
+        world.click((0, 0));
+        assert_eq!(world.ants().len(), 1);
+        assert_eq!(world.delta(), &vec![((0, 0), Cell::default())]);
+
+        // painting leaves ants alone
+        world.set_cells(vec![((2, 2), Cell::new(1, 2))]);
+        assert_eq!(world.ants().len(), 1);
+        world.click((2, 2));
+        assert!(world.ants().is_empty());
+
+        // cycling with the mouse is clicking, all along a drag
+        let last = Cell::new(1, 2);
+        world.set_cells(vec![((0, 1), last), ((1, 1), last), ((2, 1), last)]);
+        let mut input = Input::new();
+        input.press(&mut world, (0, 1), Button::Primary, Modifiers::NONE);
+        input.drag(&mut world, (2, 1));
+        assert_eq!(world.ants().len(), 3);
+        assert_eq!(world.delta().len(), 2, "the press was drawn already");
+
+        let empty = WConfig {
+            dimensions: Dimensions(0, 3),
+            ..config(Collision::Stack)
+        };
+        let world = World::random_with_ants(&mut rand::thread_rng(), empty, 3, &[]);
+        assert!(world.ants().is_empty());
+    }
+}
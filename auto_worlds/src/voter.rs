@@ -0,0 +1,300 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, History, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::neighborhood::{count_around, Neighborhood};
+
+/// One of two opinions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    No,
+    Yes,
+}
+
+auto_cellular::finite_cell!(Cell {
+    No => "No",
+    Yes => "Yes",
+});
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        match self {
+            Cell::No => Cell::Yes,
+            Cell::Yes => Cell::No,
+        }
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        if rng.gen() {
+            Cell::Yes
+        } else {
+            Cell::No
+        }
+    }
+}
+
+/// How a cell makes up its mind from its neighbors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rule {
+    /// Takes the opinion of a random neighbor
+    #[default]
+    Voter,
+    /// Takes the opinion most of its neighbors and itself hold
+    Majority,
+    /// Vichniac's twisted majority, where the two closest counts trade outcomes so that
+    /// a narrow majority loses. Flat borders between opinions stay put, while bumps in
+    /// them are worn down like they would be by annealing
+    Anneal,
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "voter" => Ok(Self::Voter),
+            "majority" => Ok(Self::Majority),
+            "anneal" | "vichniac" | "twisted" => Ok(Self::Anneal),
+            _ => Err(format!(
+                "unknown rule `{}`, try voter, majority or anneal",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rule::Voter => write!(f, "voter"),
+            Rule::Majority => write!(f, "majority"),
+            Rule::Anneal => write!(f, "anneal"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+    /// Chance that a cell ignores the rule and picks an opinion at random every tick
+    pub noise: f64,
+    pub neighborhood: Neighborhood,
+    /// Seed of the random numbers the neighbors and the noise are drawn from
+    pub seed: u64,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// How close to agreeing the cells are
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Consensus {
+    /// Share of the cells holding the most common opinion, from 0.5 to 1 at consensus
+    pub majority: f64,
+    /// Share of the pairs of neighbors that disagree, which is 0 at consensus
+    pub interfaces: f64,
+}
+
+/// How close to agreeing the cells of `cells` are, see [Consensus]
+pub fn consensus(cells: &DoubleVec<Cell>, offsets: &[(isize, isize)]) -> Consensus {
+    let (mut yes, mut disagreeing, mut cells_count) = (0, 0, 0);
+    for (at, cell) in linearize(cells.clone()) {
+        cells_count += 1;
+        if cell == Cell::Yes {
+            yes += 1;
+        }
+        disagreeing += count_around(cells, offsets, at, |c| c != cell);
+    }
+    let yes = yes as f64 / cells_count as f64;
+    Consensus {
+        majority: yes.max(1.0 - yes),
+        interfaces: disagreeing as f64 / (cells_count * offsets.len().max(1)) as f64,
+    }
+}
+
+/// Opinion dynamics: every tick, every cell takes an opinion from its neighbors at once,
+/// following the [Rule], unless noise has it pick one at random
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    offsets: Vec<(isize, isize)>,
+    rng: StdRng,
+    indices: History<Consensus>,
+}
+
+impl World {
+    /// How close to agreeing the cells were at creation and after every tick, as far back
+    /// as the [History] goes
+    pub fn consensus(&self) -> &History<Consensus> {
+        &self.indices
+    }
+
+    fn step(&self, rng: &mut StdRng) -> Vec<(Index, Cell)> {
+        let Dimensions(w, h) = self.config.dimensions;
+        // the cell itself counts in the majorities, which makes for an odd total
+        let total = self.offsets.len() + 1;
+        let mut delta = vec![];
+        for (j, row) in self.cells.iter().enumerate() {
+            for (i, &cell) in row.iter().enumerate() {
+                let next = if rng.gen_bool(self.config.noise) {
+                    Cell::random(rng)
+                } else {
+                    let yes = || {
+                        count_around(&self.cells, &self.offsets, (i, j), |c| c == Cell::Yes)
+                            + (cell == Cell::Yes) as usize
+                    };
+                    match self.config.rule {
+                        Rule::Voter => match self.offsets.choose(rng) {
+                            Some((dx, dy)) => {
+                                let x = (i as isize + dx).rem_euclid(w as isize) as usize;
+                                let y = (j as isize + dy).rem_euclid(h as isize) as usize;
+                                self.cells[y][x]
+                            }
+                            None => cell,
+                        },
+                        Rule::Majority if 2 * yes() > total => Cell::Yes,
+                        Rule::Majority => Cell::No,
+                        Rule::Anneal => match yes() {
+                            n if n == total / 2 => Cell::Yes,
+                            n if n == total / 2 + 1 => Cell::No,
+                            n if 2 * n > total => Cell::Yes,
+                            _ => Cell::No,
+                        },
+                    }
+                };
+                if next != cell {
+                    delta.push(((i, j), next));
+                }
+            }
+        }
+        delta
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        let offsets = config.neighborhood.offsets(1);
+        let mut indices = History::new();
+        indices.push(consensus(&cells, &offsets));
+        Self {
+            indices,
+            cells,
+            offsets,
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    /// What the upcoming tick does, which draws the same numbers the tick will
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        self.step(&mut self.rng.clone())
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let mut rng = self.rng.clone();
+        let delta = self.step(&mut rng);
+        for &((x, y), cell) in delta.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.rng = rng;
+        self.indices.push(consensus(&self.cells, &self.offsets));
+        self.delta = delta;
+    }
+
+    /// Painted opinions are measured again as part of the last tick
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.indices.revise(consensus(&self.cells, &self.offsets));
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(rule: Rule, noise: f64) -> WConfig {
+        WConfig {
+            dimensions: Dimensions(16, 16),
+            rule,
+            noise,
+            neighborhood: Neighborhood::Moore,
+            seed: 8,
+        }
+    }
+
+    #[test]
+    fn majorities_silence_dissenters() {
+        for rule in [Rule::Majority, Rule::Anneal] {
+            assert_eq!(rule.to_string().parse(), Ok(rule));
+            let mut world = World::new_blank(config(rule, 0.0));
+            world.set_cells(vec![((4, 4), Cell::Yes), ((9, 9), Cell::Yes)]);
+            let before = world.consensus()[0];
+            assert_eq!(before, consensus(world.cells(), &world.offsets));
+            assert_eq!(before.majority, 254.0 / 256.0);
+            assert_eq!(before.interfaces, 32.0 / (256.0 * 8.0));
+            world.tick();
+            assert_eq!(world.consensus()[1].majority, 1.0);
+            assert_eq!(world.consensus()[1].interfaces, 0.0);
+        }
+
+        // a narrow majority of 5 out of 9 loses under annealing
+        let mut cells = vec![vec![Cell::No; 16]; 16];
+        for (x, y) in [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1)] {
+            cells[y][x] = Cell::Yes;
+        }
+        let world = World::new(cells, config(Rule::Anneal, 0.0));
+        assert!(world.changes().contains(&((1, 1), Cell::No)));
+    }
+
+    #[test]
+    fn voters_copy_and_noise_mixes() {
+        let mut agreed = World::new(vec![vec![Cell::Yes; 16]; 16], config(Rule::Voter, 0.0));
+        agreed.tick();
+        assert!(agreed.delta().is_empty());
+
+        let mut noisy = World::new(vec![vec![Cell::Yes; 16]; 16], config(Rule::Voter, 1.0));
+        noisy.tick();
+        let mixed = noisy.consensus()[1];
+        assert!(mixed.majority < 0.6);
+        assert!(mixed.interfaces > 0.4);
+    }
+}
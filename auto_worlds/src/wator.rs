@@ -0,0 +1,393 @@
+use auto_cellular::{
+    cell::{CellLike, Census, FiniteCellLike},
+    common::{diff, linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+/// A patch of ocean and whoever swims in it. `age` counts the ticks since the animal was
+/// born or last bred, and `hunger` the ticks since the shark last ate
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    Water,
+    Fish {
+        age: u16,
+    },
+    Shark {
+        age: u16,
+        hunger: u16,
+    },
+}
+
+impl Cell {
+    /// The same animal right after it bred
+    fn bred(self) -> Self {
+        match self {
+            Cell::Fish { .. } => Cell::Fish { age: 0 },
+            Cell::Shark { hunger, .. } => Cell::Shark { age: 0, hunger },
+            Cell::Water => Cell::Water,
+        }
+    }
+
+    /// A newborn of the same kind
+    fn newborn(self) -> Self {
+        Self::from_index(self.to_index()).unwrap()
+    }
+}
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        match self {
+            Cell::Water => Cell::Fish { age: 0 },
+            Cell::Fish { .. } => Cell::Shark { age: 0, hunger: 0 },
+            Cell::Shark { .. } => Cell::Water,
+        }
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        match rng.gen_range(0..20) {
+            0..=5 => Cell::Fish { age: 0 },
+            6 => Cell::Shark { age: 0, hunger: 0 },
+            _ => Cell::Water,
+        }
+    }
+}
+
+/// Cells are told apart by what lives there, whatever their counters
+impl FiniteCellLike for Cell {
+    const STATE_COUNT: usize = 3;
+
+    fn to_index(&self) -> usize {
+        match self {
+            Cell::Water => 0,
+            Cell::Fish { .. } => 1,
+            Cell::Shark { .. } => 2,
+        }
+    }
+
+    fn from_index(index: usize) -> Option<Self> {
+        [
+            Cell::Water,
+            Cell::Fish { age: 0 },
+            Cell::Shark { age: 0, hunger: 0 },
+        ]
+        .get(index)
+        .copied()
+    }
+
+    fn state_name(&self) -> &'static str {
+        ["Water", "Fish", "Shark"][self.to_index()]
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    /// Ticks a fish lives before it can breed
+    pub fish_breed: u16,
+    /// Ticks a shark lives before it can breed
+    pub shark_breed: u16,
+    /// Ticks a shark lasts without eating
+    pub starve: u16,
+    /// Seed of the random numbers deciding who goes first and where they go
+    pub seed: u64,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// Wa-Tor: fish and sharks on a toroidal ocean. Every tick, the animals take turns in a
+/// random order: fish swim to a free neighboring cell, sharks to a neighboring fish
+/// they eat (or a free cell when there is none), and whoever is old enough leaves a
+/// newborn behind. Sharks that go hungry for too long die
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    rng: StdRng,
+    census: Census,
+}
+
+impl World {
+    /// How much water, fish and sharks there were over the last ticks, see [Census]
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    /// The `(fish, sharks)` after every tick the census kept, which trace the predator-prey
+    /// cycle
+    pub fn populations(&self) -> Vec<(usize, usize)> {
+        let fish = self.census.series(Cell::Fish { age: 0 });
+        let sharks = self.census.series(Cell::Shark { age: 0, hunger: 0 });
+        fish.into_iter().zip(sharks).collect()
+    }
+
+    /// The populations as comma separated values, for plotting one against the other
+    pub fn phase_csv(&self) -> String {
+        let mut csv = String::from("tick,fish,sharks\n");
+        let ticks = self.census.first_tick()..;
+        for (tick, (fish, sharks)) in ticks.zip(self.populations()) {
+            csv += &format!("{},{},{}\n", tick, fish, sharks);
+        }
+        csv
+    }
+
+    /// The grid after every animal took its turn, drawing from `rng`
+    fn step(&self, rng: &mut StdRng) -> DoubleVec<Cell> {
+        let Dimensions(w, h) = self.config.dimensions;
+        let mut cells = self.cells.clone();
+        let mut animals: Vec<Index> = linearize(self.cells.clone())
+            .into_iter()
+            .filter(|(_, c)| *c != Cell::Water)
+            .map(|(i, _)| i)
+            .collect();
+        animals.shuffle(rng);
+        // where the animals that already took their turn are, so they don't go twice
+        let mut moved = vec![vec![false; w]; h];
+
+        for (x, y) in animals {
+            if moved[y][x] {
+                continue;
+            }
+            let around: Vec<Index> = [(1, 0), (w - 1, 0), (0, 1), (0, h - 1)]
+                .iter()
+                .map(|(dx, dy)| ((x + dx) % w, (y + dy) % h))
+                .filter(|&p| p != (x, y))
+                .collect();
+            let free = |cells: &DoubleVec<Cell>, f: fn(Cell) -> bool| {
+                around
+                    .iter()
+                    .copied()
+                    .filter(|&(i, j)| f(cells[j][i]))
+                    .collect::<Vec<_>>()
+            };
+
+            // the animal one tick older, where it goes, and whether it breeds there
+            let (aged, to, breeds) = match cells[y][x] {
+                Cell::Water => continue,
+                Cell::Fish { age } => {
+                    let to = free(&cells, |c| c == Cell::Water).choose(rng).copied();
+                    let age = age.saturating_add(1);
+                    (Cell::Fish { age }, to, age >= self.config.fish_breed)
+                }
+                Cell::Shark { age, hunger } => {
+                    let fish = free(&cells, |c| matches!(c, Cell::Fish { .. }));
+                    let (to, hunger) = match fish.choose(rng) {
+                        Some(&to) => (Some(to), 0),
+                        None => (
+                            free(&cells, |c| c == Cell::Water).choose(rng).copied(),
+                            hunger.saturating_add(1),
+                        ),
+                    };
+                    if hunger >= self.config.starve {
+                        cells[y][x] = Cell::Water;
+                        continue;
+                    }
+                    let age = age.saturating_add(1);
+                    (
+                        Cell::Shark { age, hunger },
+                        to,
+                        age >= self.config.shark_breed,
+                    )
+                }
+            };
+
+            match to {
+                Some((i, j)) => {
+                    if breeds {
+                        cells[j][i] = aged.bred();
+                        cells[y][x] = aged.newborn();
+                    } else {
+                        cells[j][i] = aged;
+                        cells[y][x] = Cell::Water;
+                    }
+                    moved[j][i] = true;
+                    moved[y][x] = true;
+                }
+                // nowhere to go, so no room for a newborn either
+                None => cells[y][x] = aged,
+            }
+        }
+        cells
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        let mut census = Census::new();
+        census.record(&cells);
+        Self {
+            cells,
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+            delta: linearize(clone),
+            census,
+        }
+    }
+
+    /// Animals of random ages, so they don't all breed at once
+    fn new_random<R: rand::Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        let Dimensions(w, h) = config.dimensions;
+        let cells = (0..h)
+            .map(|_| {
+                (0..w)
+                    .map(|_| match Cell::random(rng) {
+                        Cell::Fish { .. } => Cell::Fish {
+                            age: rng.gen_range(0..config.fish_breed.max(1)),
+                        },
+                        Cell::Shark { .. } => Cell::Shark {
+                            age: rng.gen_range(0..config.shark_breed.max(1)),
+                            hunger: 0,
+                        },
+                        Cell::Water => Cell::Water,
+                    })
+                    .collect()
+            })
+            .collect();
+        Self::new(cells, config)
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    /// What the upcoming tick does, which draws the same numbers the tick will
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        diff(&self.cells, &self.step(&mut self.rng.clone()))
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn tick(&mut self) {
+        let mut rng = self.rng.clone();
+        let cells = self.step(&mut rng);
+        self.delta = diff(&self.cells, &cells);
+        self.cells = cells;
+        self.rng = rng;
+        self.census.record(&self.cells);
+    }
+
+    /// Animals put in by clicking or painting are counted in the census of the last tick
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.census.revise(&self.cells);
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(w: usize, h: usize) -> WConfig {
+        WConfig {
+            dimensions: Dimensions(w, h),
+            fish_breed: 3,
+            shark_breed: 10,
+            starve: 4,
+            seed: 11,
+        }
+    }
+
+    #[test]
+    fn fish_breed_and_sharks_starve() {
+        let mut cells = vec![vec![Cell::Water; 10]; 10];
+        cells[2][2] = Cell::Fish { age: 0 };
+        cells[7][7] = Cell::Shark { age: 0, hunger: 0 };
+        let mut world = World::new(cells, config(10, 10));
+        for _ in 0..6 {
+            world.tick();
+        }
+        let populations = world.populations();
+        // the fish doubles every 3 ticks, and the shark never meets one
+        assert_eq!(populations[..4], [(1, 1), (1, 1), (1, 1), (2, 1)]);
+        assert_eq!(populations[4..], [(2, 0), (2, 0), (4, 0)]);
+        assert!(world.phase_csv().starts_with("tick,fish,sharks\n0,1,1\n"));
+
+        // clicked in animals count towards the last tick
+        world.click((0, 0));
+        world.click((9, 0));
+        world.click((9, 0));
+        assert_eq!(world.populations().len(), 7);
+        assert!(world.phase_csv().ends_with("\n6,5,1\n"));
+    }
+
+    #[test]
+    fn boxed_in_animals_stop_aging_at_the_limit() {
+        let old = u16::MAX - 1;
+        let fish = World::new(vec![vec![Cell::Fish { age: old }; 4]; 4], config(4, 4));
+        let sharks = vec![
+            vec![
+                Cell::Shark {
+                    age: old,
+                    hunger: 0
+                };
+                4
+            ];
+            4
+        ];
+        let sharks = World::new(
+            sharks,
+            WConfig {
+                starve: u16::MAX,
+                ..config(4, 4)
+            },
+        );
+        for (mut world, oldest) in [
+            (fish, Cell::Fish { age: u16::MAX }),
+            (
+                sharks,
+                Cell::Shark {
+                    age: u16::MAX,
+                    hunger: 2,
+                },
+            ),
+        ] {
+            // nobody can move in a packed ocean, so nobody breeds either
+            world.tick();
+            world.tick();
+            assert!(world.cells().iter().flatten().all(|&c| c == oldest));
+        }
+    }
+
+    #[test]
+    fn sharks_eat_their_neighbors() {
+        let mut cells = vec![vec![Cell::Water; 3]];
+        cells[0][0] = Cell::Shark { age: 0, hunger: 3 };
+        cells[0][1] = Cell::Fish { age: 0 };
+        let mut world = World::new(cells, config(3, 1));
+        let changes = world.changes();
+        world.tick();
+        assert_eq!(world.delta(), &changes);
+        assert_eq!(world.populations()[1], (0, 1));
+        assert!(world
+            .cells()
+            .iter()
+            .flatten()
+            .any(|&c| c == Cell::Shark { age: 1, hunger: 0 }));
+    }
+}
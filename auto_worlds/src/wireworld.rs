@@ -0,0 +1,276 @@
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+
+use crate::PROPORTION;
+
+#[derive(Clone, Default, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Conductor,
+    Head,
+    Tail,
+}
+
+auto_cellular::finite_cell!(Cell {
+    Empty => "Empty",
+    Conductor => "Conductor",
+    Head => "Electron head",
+    Tail => "Electron tail",
+});
+
+impl Cell {
+    /// The character standing for the cell in a circuit drawing
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' | ' ' => Some(Cell::Empty),
+            '#' => Some(Cell::Conductor),
+            '@' => Some(Cell::Head),
+            '~' => Some(Cell::Tail),
+            _ => None,
+        }
+    }
+}
+
+impl CellLike for Cell {
+    /// Clicking an empty cell lays conductor right away
+    fn next_state(&self) -> Self {
+        match *self {
+            Cell::Empty => Cell::Conductor,
+            Cell::Conductor => Cell::Head,
+            Cell::Head => Cell::Tail,
+            Cell::Tail => Cell::Empty,
+        }
+    }
+
+    fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        if rng.gen_bool(PROPORTION) {
+            Cell::Empty
+        } else if rng.gen_bool(0.8) {
+            Cell::Conductor
+        } else {
+            Cell::Head
+        }
+    }
+}
+
+/// Circuits drawn with `.` for empty, `#` for conductor, `@` for an electron's head
+/// and `~` for its tail. Every input starts out with an electron on it
+pub const PRESETS: [(&str, &str); 4] = [
+    (
+        // the top diode lets the electron through, the bottom one is reversed
+        "diode",
+        "\
+...##.....
+~@##.#####
+...##.....
+..........
+.....##...
+~@###.####
+.....##...",
+    ),
+    (
+        // an electron circling a loop sends a pulse down the wire every 6 ticks
+        "clock",
+        "\
+.##.........
+#..#########
+.~@.........",
+    ),
+    (
+        "or",
+        "\
+...##..........
+~@##.####......
+...##...#......
+........#######
+...##...#......
+~@##.####......
+...##..........",
+    ),
+    (
+        // two electrons arriving together cancel out
+        "xor",
+        "\
+~@##.......
+....#......
+...####....
+...#..#####
+...####....
+....#......
+~@##.......",
+    ),
+];
+
+/// Parses a circuit drawn like the [PRESETS]
+pub fn parse_circuit(drawing: &str) -> Result<DoubleVec<Cell>, String> {
+    drawing
+        .lines()
+        .map(|line| {
+            line.chars()
+                .map(|c| Cell::from_char(c).ok_or_else(|| format!("`{}` is not a cell", c)))
+                .collect()
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+}
+
+impl World {
+    /// A blank world with the circuit of the preset called `name` in its middle
+    pub fn from_preset(name: &str, config: WConfig) -> Result<Self, String> {
+        let (_, drawing) = PRESETS
+            .iter()
+            .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("unknown circuit `{}`", name))?;
+        Self::with_circuit(parse_circuit(drawing)?, config)
+    }
+
+    /// A blank world with `circuit` in its middle
+    pub fn with_circuit(circuit: DoubleVec<Cell>, config: WConfig) -> Result<Self, String> {
+        let Dimensions(width, height) = config.dimensions;
+        let circuit_width = circuit.iter().map(|row| row.len()).max().unwrap_or(0);
+        if circuit_width > width || circuit.len() > height {
+            return Err(format!(
+                "the circuit needs {}x{} cells but the world is {}x{}",
+                circuit_width,
+                circuit.len(),
+                width,
+                height
+            ));
+        }
+
+        let (left, top) = ((width - circuit_width) / 2, (height - circuit.len()) / 2);
+        let mut cells = vec![vec![Cell::Empty; width]; height];
+        for (j, row) in circuit.into_iter().enumerate() {
+            for (i, cell) in row.into_iter().enumerate() {
+                cells[top + j][left + i] = cell;
+            }
+        }
+        Ok(Self::new(cells, config))
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+        }
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let mut delta = vec![];
+
+        for j in 0..self.config().dimensions().1 {
+            for i in 0..self.config().dimensions().0 {
+                let p = (i, j);
+                match self.cells[j][i] {
+                    Cell::Empty => {}
+                    Cell::Head => delta.push((p, Cell::Tail)),
+                    Cell::Tail => delta.push((p, Cell::Conductor)),
+                    Cell::Conductor => {
+                        let heads = self
+                            .moore_neighbors(p)
+                            .iter()
+                            .filter(|c| matches!(self.cells[c.1][c.0], Cell::Head))
+                            .count();
+                        if heads == 1 || heads == 2 {
+                            delta.push((p, Cell::Head))
+                        }
+                    }
+                }
+            }
+        }
+        delta
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Ticks the preset and counts the electrons that reach the end of the wire that
+    /// leaves it on the right, on the row `row` of the drawing
+    fn outputs(preset: &str, row: usize, inputs: &[bool]) -> usize {
+        let (_, drawing) = PRESETS.iter().find(|(name, _)| *name == preset).unwrap();
+        let mut circuit = parse_circuit(drawing).unwrap();
+        // turn the electrons of the inputs that are off back into conductor
+        let mut rows = circuit.iter_mut().filter(|r| r[1] == Cell::Head);
+        for &on in inputs {
+            let input = rows.next().unwrap();
+            if !on {
+                input[..2].fill(Cell::Conductor);
+            }
+        }
+
+        let width = circuit[0].len();
+        let config = WConfig {
+            dimensions: Dimensions(width + 2, circuit.len() + 2),
+        };
+        let mut world = World::with_circuit(circuit, config).unwrap();
+        let mut count = 0;
+        for _ in 0..40 {
+            world.tick();
+            count += (world.cells()[row + 1][width] == Cell::Head) as usize;
+        }
+        count
+    }
+
+    #[test]
+    fn diode_only_conducts_one_way() {
+        assert_eq!(outputs("diode", 1, &[true, true]), 1);
+        assert_eq!(outputs("diode", 5, &[true, true]), 0);
+    }
+
+    #[test]
+    fn gates_compute() {
+        for (a, b) in [(true, false), (false, true), (true, true)] {
+            assert_eq!(outputs("or", 3, &[a, b]), 1);
+            assert_eq!(outputs("xor", 3, &[a, b]), (a != b) as usize);
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use std::{fmt::Display, str::FromStr};
+
+use auto_cellular::{
+    cell::CellLike,
+    common::{linearize, Dimensions, DoubleVec, Index},
+    world::{WorldConfig, WorldLike},
+};
+use rand::Rng;
+
+/// A cell of a one dimensional automaton with `colors` colors
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    color: u8,
+    colors: u8,
+}
+
+impl Cell {
+    pub fn new(color: u8, colors: u8) -> Self {
+        Self {
+            color: color % colors,
+            colors,
+        }
+    }
+
+    pub fn color(&self) -> u8 {
+        self.color
+    }
+
+    /// The color scaled to lie between 0 and 1
+    pub fn level(&self) -> f64 {
+        self.color as f64 / (self.colors - 1) as f64
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(0, 2)
+    }
+}
+
+impl CellLike for Cell {
+    fn next_state(&self) -> Self {
+        Self::new(self.color + 1, self.colors)
+    }
+
+    fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(rng.gen_range(0..2), 2)
+    }
+}
+
+/// How a row turns into the next one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// One of Wolfram's 256 rules of 2 colors and radius 1, where bit `4l + 2c + r` of
+    /// the number is the next color of a cell `c` between `l` and `r`
+    Elementary(u8),
+    /// The next color only depends on the sum of the colors within `radius` of a cell.
+    /// `table[sum]` is that color
+    Totalistic {
+        colors: u8,
+        radius: usize,
+        table: Vec<u8>,
+    },
+}
+
+impl Rule {
+    pub fn colors(&self) -> u8 {
+        match self {
+            Rule::Elementary(_) => 2,
+            Rule::Totalistic { colors, .. } => *colors,
+        }
+    }
+
+    pub fn radius(&self) -> usize {
+        match self {
+            Rule::Elementary(_) => 1,
+            Rule::Totalistic { radius, .. } => *radius,
+        }
+    }
+
+    /// Computes the row that follows `row`, which wraps around at its ends
+    pub fn next_row(&self, row: &[u8]) -> Vec<u8> {
+        let n = row.len() as isize;
+        let r = self.radius() as isize;
+        let at = |i: isize| row[i.rem_euclid(n) as usize];
+        (0..n)
+            .map(|i| match self {
+                Rule::Elementary(number) => {
+                    let pattern = at(i - 1) << 2 | at(i) << 1 | at(i + 1);
+                    (number >> pattern) & 1
+                }
+                Rule::Totalistic { table, .. } => {
+                    let sum: usize = (i - r..=i + r).map(|j| at(j) as usize).sum();
+                    table[sum]
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::Elementary(30)
+    }
+}
+
+/// The widest reach of a totalistic rule, beyond which its table gets unreasonably large
+pub const MAX_RADIUS: usize = 16;
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses elementary rules as their number (`30`, `W30` or `rule30`) and totalistic
+    /// ones as `K3,R1,T1635`: `K` colors, radius `R` (1 if left out) and Wolfram's code `T`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_uppercase();
+        let number = s
+            .strip_prefix("RULE")
+            .or_else(|| s.strip_prefix('W'))
+            .unwrap_or(&s);
+        if let Ok(number) = number.parse::<u8>() {
+            return Ok(Rule::Elementary(number));
+        }
+
+        let (mut colors, mut radius, mut code) = (None, 1, None);
+        for part in s.split(',') {
+            let part = part.trim();
+            let bad = || format!("`{}` is not a valid part", part);
+            let key = part.chars().next().ok_or_else(bad)?;
+            let value = &part[key.len_utf8()..];
+            match key {
+                'K' => colors = Some(value.parse::<u8>().map_err(|_| bad())?),
+                'R' => radius = value.parse::<usize>().map_err(|_| bad())?,
+                'T' => code = Some(value.parse::<u128>().map_err(|_| bad())?),
+                _ => return Err(bad()),
+            }
+        }
+
+        let colors = colors.ok_or_else(|| format!("`{}` is missing the colors `K`", s))?;
+        let mut code = code.ok_or_else(|| format!("`{}` is missing the code `T`", s))?;
+        if colors < 2 {
+            return Err(String::from("a rule needs at least 2 colors"));
+        }
+        if radius > MAX_RADIUS {
+            return Err(format!("the radius can be at most {}", MAX_RADIUS));
+        }
+
+        let sums = (2 * radius + 1)
+            .checked_mul(colors as usize - 1)
+            .and_then(|sums| sums.checked_add(1))
+            .ok_or_else(|| format!("`{}` has too many sums", s))?;
+        let mut table = Vec::with_capacity(sums);
+        for _ in 0..sums {
+            table.push((code % colors as u128) as u8);
+            code /= colors as u128;
+        }
+        if code != 0 {
+            return Err(format!("the code is too big for {} sums", sums));
+        }
+
+        Ok(Rule::Totalistic {
+            colors,
+            radius,
+            table,
+        })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rule::Elementary(number) => write!(f, "W{}", number),
+            Rule::Totalistic {
+                colors,
+                radius,
+                table,
+            } => {
+                let code = table
+                    .iter()
+                    .rev()
+                    .fold(0u128, |code, &c| code * *colors as u128 + c as u128);
+                write!(f, "K{},R{},T{}", colors, radius, code)
+            }
+        }
+    }
+}
+
+/// What the first row looks like
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Seed {
+    /// One cell of the highest color in the middle
+    #[default]
+    Single,
+    /// Every cell of a random color
+    Random,
+}
+
+impl FromStr for Seed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "single" => Ok(Self::Single),
+            "random" => Ok(Self::Random),
+            _ => Err(format!("unknown seed `{}`", s)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WConfig {
+    pub dimensions: Dimensions,
+    pub rule: Rule,
+    pub seed: Seed,
+}
+
+impl WorldConfig for WConfig {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+}
+
+/// A one dimensional automaton drawn as a spacetime diagram: every generation is the row
+/// under the previous one, and once the grid is full the diagram scrolls up
+pub struct World {
+    cells: DoubleVec<Cell>,
+    config: WConfig,
+    delta: Vec<(Index, Cell)>,
+    /// The row holding the current generation
+    row: usize,
+}
+
+impl World {
+    /// A world whose first row is seeded according to [WConfig::seed]
+    pub fn seeded<R: Rng + ?Sized>(rng: &mut R, config: WConfig) -> Self {
+        let Dimensions(width, height) = config.dimensions;
+        let colors = config.rule.colors();
+        let mut cells = vec![vec![Cell::new(0, colors); width]; height];
+        // an empty world has no first row to seed
+        if let Some(first) = cells.first_mut() {
+            match config.seed {
+                Seed::Single => {
+                    if let Some(middle) = first.get_mut(width / 2) {
+                        *middle = Cell::new(colors - 1, colors)
+                    }
+                }
+                Seed::Random => {
+                    for cell in first.iter_mut() {
+                        *cell = Cell::new(rng.gen_range(0..colors), colors);
+                    }
+                }
+            }
+        }
+        Self::new(cells, config)
+    }
+
+    /// How many generations are on screen
+    pub fn generations(&self) -> usize {
+        self.row + 1
+    }
+}
+
+impl WorldLike for World {
+    type Cell = Cell;
+    type Config = WConfig;
+
+    /// The first row of `cells` is the current generation
+    fn new(cells: DoubleVec<Cell>, config: Self::Config) -> Self {
+        let colors = config.rule.colors();
+        let cells: DoubleVec<Cell> = cells
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|c| Cell::new(c.color, colors))
+                    .collect()
+            })
+            .collect();
+        let clone = cells.clone();
+        Self {
+            cells,
+            config,
+            delta: linearize(clone),
+            row: 0,
+        }
+    }
+
+    /// Only the first row is random, as given by [WConfig::seed]
+    fn new_random<R: Rng + ?Sized>(rng: &mut R, config: Self::Config) -> Self {
+        Self::seeded(rng, config)
+    }
+
+    fn cells(&self) -> &DoubleVec<Cell> {
+        &self.cells
+    }
+
+    fn cells_mut(&mut self) -> &mut DoubleVec<Cell> {
+        &mut self.cells
+    }
+
+    fn changes(&self) -> Vec<(Index, Cell)> {
+        let colors = self.config.rule.colors();
+        let current: Vec<u8> = match self.cells.get(self.row) {
+            Some(row) => row.iter().map(|c| c.color).collect(),
+            None => return vec![],
+        };
+        let next: Vec<Cell> = self
+            .config
+            .rule
+            .next_row(&current)
+            .into_iter()
+            .map(|c| Cell::new(c, colors))
+            .collect();
+
+        let height = self.cells.len();
+        let mut delta = vec![];
+        if self.row + 1 < height {
+            for (i, cell) in next.into_iter().enumerate() {
+                if cell != self.cells[self.row + 1][i] {
+                    delta.push(((i, self.row + 1), cell));
+                }
+            }
+        } else {
+            // scroll everything one row up, the new generation taking the last row
+            for j in 0..height {
+                let row = if j + 1 < height {
+                    &self.cells[j + 1]
+                } else {
+                    &next
+                };
+                for (i, &cell) in row.iter().enumerate() {
+                    if cell != self.cells[j][i] {
+                        delta.push(((i, j), cell));
+                    }
+                }
+            }
+        }
+        delta
+    }
+
+    fn tick(&mut self) {
+        let changes = self.changes();
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.row = (self.row + 1).min(self.cells.len().saturating_sub(1));
+        self.delta = changes;
+    }
+
+    fn delta(&self) -> &Vec<(Index, Cell)> {
+        &self.delta
+    }
+
+    fn delta_mut(&mut self) -> &mut Vec<(Index, Cell)> {
+        &mut self.delta
+    }
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    /// Painted cells get the number of colors of the rule
+    fn set_cells(&mut self, changes: Vec<(Index, Cell)>) {
+        let colors = self.config.rule.colors();
+        let changes: Vec<(Index, Cell)> = changes
+            .into_iter()
+            .map(|(i, c)| (i, Cell::new(c.color, colors)))
+            .collect();
+        for &((x, y), cell) in changes.iter() {
+            self.cells[y][x] = cell;
+        }
+        self.delta = changes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!("30".parse(), Ok(Rule::Elementary(30)));
+        assert_eq!("rule110".parse(), Ok(Rule::Elementary(110)));
+
+        let rule: Rule = "K3,R1,T1635".parse().unwrap();
+        assert_eq!(rule.colors(), 3);
+        assert_eq!(rule.to_string(), "K3,R1,T1635");
+        assert!("K2,R1,T256".parse::<Rule>().is_err());
+        assert!("K3".parse::<Rule>().is_err());
+        assert!("é5".parse::<Rule>().is_err());
+        assert!("K3,R1,T".parse::<Rule>().is_err());
+        assert_eq!(
+            "K3,R99999999999,T1".parse::<Rule>(),
+            Err(format!("the radius can be at most {}", MAX_RADIUS))
+        );
+        assert!("K3,R16,T1".parse::<Rule>().is_ok());
+    }
+
+    #[test]
+    fn rule_90_draws_a_sierpinski_triangle() {
+        let config = WConfig {
+            dimensions: Dimensions(7, 3),
+            rule: Rule::Elementary(90),
+            seed: Seed::Single,
+        };
+        let mut world = World::seeded(&mut rand::thread_rng(), config);
+        world.tick();
+        world.tick();
+        world.tick();
+
+        let rows: Vec<String> = world
+            .cells()
+            .iter()
+            .map(|row| row.iter().map(|c| c.color().to_string()).collect())
+            .collect();
+        // the first generation scrolled away
+        assert_eq!(rows, vec!["0010100", "0100010", "1010101"]);
+        assert_eq!(world.generations(), 3);
+
+        for dimensions in [Dimensions(0, 3), Dimensions(7, 0)] {
+            let config = WConfig {
+                dimensions,
+                rule: Rule::Elementary(90),
+                seed: Seed::Single,
+            };
+            let mut empty = World::seeded(&mut rand::thread_rng(), config);
+            empty.tick();
+            assert!(empty.delta().is_empty());
+        }
+    }
+}